@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::geometry::altitude::AltitudeReference;
+
 /// 本クレートで発生し得るエラーを表します。
 #[derive(Debug, Error, PartialEq)]
 pub enum Error {
@@ -40,4 +42,79 @@ pub enum Error {
     /// 有効範囲は Web Mercator 投影を前提とした`-85.0511 ..= 85.0511` です。
     #[error("Altitude '{altitude}' is out of range (valid: -33,554,432.0..=33,554,432.0)")]
     AltitudeOutOfRange { altitude: f64 },
+
+    /// メッシュの面が参照する頂点インデックスが、頂点配列の範囲外であることを示します。
+    #[error("Vertex index '{index}' is out of range (vertices: {len})")]
+    VertexIndexOutOfRange { index: usize, len: usize },
+
+    /// 半径が負の値、または数値でないことを示します。
+    #[error("Radius '{radius}' is out of range (valid: 0.0..)")]
+    RadiusOutOfRange { radius: f64 },
+
+    /// 楕円体の半径や直方体の半辺長が正の値でない、または数値でないことを示します。
+    #[error("Extent '{extent}' is out of range (valid: greater than 0.0)")]
+    ExtentOutOfRange { extent: f64 },
+
+    /// 平面の法線ベクトルの長さが 0、または数値でないことを示します。
+    #[error("Normal vector '{normal:?}' is invalid (valid: finite with non-zero length)")]
+    NormalOutOfRange { normal: [f64; 3] },
+
+    /// カメラの画角が `0.0..180.0` の範囲外、または描画範囲の近端と遠端が不正であることを示します。
+    #[error(
+        "Camera with fov = '{fov}', near = '{near}' and far = '{far}' is invalid (valid: 0.0 < fov < 180.0, 0.0 <= near < far < inf)"
+    )]
+    CameraOutOfRange { fov: f64, near: f64, far: f64 },
+
+    /// 曲線の制御点の個数が、曲線の種類に対して不正であることを示します。
+    #[error("Number of control points '{len}' is invalid for the curve")]
+    ControlPointCount { len: usize },
+
+    /// 楕円体の長半径が正でない、または扁平率が `0.0..1.0` の範囲外であることを示します。
+    #[error("Ellipsoid with a = '{a}' and f = '{f}' is invalid (valid: a > 0.0, 0.0 <= f < 1.0)")]
+    EllipsoidOutOfRange { a: f64, f: f64 },
+
+    /// 格子状のデータ（補正パラメータ・ジオイド高・標高）のファイルを読み込めないことを示します。
+    #[error("Failed to read grid file '{path}': {message}")]
+    GridFileRead { path: String, message: String },
+
+    /// 格子状のデータのファイルの行を解釈できないことを示します。
+    #[error("Line {line} of the grid file is malformed")]
+    GridFileParse { line: usize },
+
+    /// 座標が格子状のデータの範囲外、または欠測値の位置にあることを示します。
+    #[error("Coordinate ({latitude}, {longitude}) is outside the grid data")]
+    GridOutOfRange { latitude: f64, longitude: f64 },
+
+    /// 平面直角座標系や UTM の系（ゾーン）の番号が有効範囲外であることを示します。
+    #[error("Zone '{zone}' is out of range for the coordinate system")]
+    ZoneOutOfRange { zone: u8 },
+
+    /// 緯度が UTM・MGRS で扱える範囲外であることを示します。
+    ///
+    /// 有効範囲は `-80.0 ..= 84.0` です。
+    #[error("Latitude '{latitude}' is out of range for UTM (valid: -80.0..=84.0)")]
+    UtmLatitudeOutOfRange { latitude: f64 },
+
+    /// MGRS の文字列を解釈できないことを示します。
+    #[error("MGRS reference '{mgrs}' is malformed")]
+    MgrsParse { mgrs: String },
+
+    /// 高度の基準の変換に必要なジオイドモデルまたは地表面が与えられていないことを示します。
+    #[error(
+        "Converting altitude from {from:?} to {to:?} requires a geoid or terrain model that was not supplied"
+    )]
+    AltitudeModelMissing {
+        from: AltitudeReference,
+        to: AltitudeReference,
+    },
+
+    /// 緯度が楕円体上の緯度として有効範囲外であることを示します。
+    ///
+    /// 有効範囲は極を含む `-90.0 ..= 90.0` です。
+    #[error("Latitude '{latitude}' is out of range for a geodetic position (valid: -90.0..=90.0)")]
+    GeodeticLatitudeOutOfRange { latitude: f64 },
+
+    /// 高度が有限の数値でないことを示します。
+    #[error("Altitude '{altitude}' is not a finite number")]
+    AltitudeNotFinite { altitude: f64 },
 }
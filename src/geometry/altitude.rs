@@ -0,0 +1,147 @@
+//! 高度の基準と、基準の間の変換
+//!
+//! [`Coordinate`](crate::geometry::coordinate::Coordinate) の高度は `f64` の値のみを持ち、
+//! どの面から測った高さであるかを区別しません。
+//! [`Coordinate::to_single_id`](crate::geometry::coordinate::Coordinate::to_single_id) や
+//! [`Ecef`](crate::geometry::ecef::Ecef) への変換は高度を楕円体高として扱うため、
+//! 標高や対地高度で表されたデータは
+//! [`AltitudeReference`](crate::geometry::altitude::AltitudeReference) を明示して変換します。
+//!
+//! 基準の間の変換には次のモデルを用います。必要なモデルが与えられていない場合は、
+//! 基準を混同したまま変換することのないよう
+//! [`Error::AltitudeModelMissing`](crate::error::Error::AltitudeModelMissing) を返します。
+//!
+//! * 楕円体高と標高の変換: [`Geoid`](crate::geometry::geoid::Geoid)
+//! * 標高と対地高度の変換: [`Terrain`](crate::geometry::terrain::Terrain)
+
+use std::fmt;
+
+use crate::{
+    error::Error,
+    geometry::{coordinate::Coordinate, geoid::Geoid, terrain::Terrain},
+};
+
+/// 高度の基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AltitudeReference {
+    /// 楕円体高（WGS-84 楕円体面からの高さ）
+    #[default]
+    Ellipsoidal,
+    /// 標高（ジオイド面からの高さ）
+    Orthometric,
+    /// 対地高度（地表面からの高さ）
+    Agl,
+}
+
+/// 高度の基準の変換に用いるジオイドモデルと地表面
+///
+/// ```
+/// # use kasane_logic::error::Error;
+/// # use kasane_logic::geometry::altitude::{AltitudeModels, AltitudeReference};
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::geoid::GridGeoid;
+/// # use kasane_logic::geometry::terrain::Terrain;
+/// let geoid = GridGeoid::from_gsigeo_str(
+///     "35.0 139.0 0.5 0.5 2 2 1 ver2.2\n36.0 36.0\n36.0 36.0\n",
+/// )
+/// .unwrap();
+/// let terrain = Terrain::from_esri_ascii_str(
+///     "ncols 2\nnrows 2\nxllcorner 139.0\nyllcorner 35.0\ncellsize 0.01\n40 40\n40 40\n",
+/// )
+/// .unwrap();
+/// let models = AltitudeModels::new(Some(&geoid), Some(&terrain));
+///
+/// // 地表から 150m は、標高 190m、楕円体高 226m
+/// let drone = Coordinate::new(35.005, 139.005, 150.0).unwrap();
+/// let ellipsoidal = models
+///     .convert(&drone, AltitudeReference::Agl, AltitudeReference::Ellipsoidal)
+///     .unwrap();
+/// assert!((ellipsoidal.as_altitude() - 226.0).abs() < 1e-9);
+///
+/// // ジオイドモデルがなければ楕円体高には変換できない
+/// let terrain_only = AltitudeModels::new(None, Some(&terrain));
+/// assert_eq!(
+///     terrain_only.convert(&drone, AltitudeReference::Agl, AltitudeReference::Ellipsoidal),
+///     Err(Error::AltitudeModelMissing {
+///         from: AltitudeReference::Agl,
+///         to: AltitudeReference::Ellipsoidal,
+///     })
+/// );
+/// // 標高までであれば変換できる
+/// assert!(terrain_only
+///     .convert(&drone, AltitudeReference::Agl, AltitudeReference::Orthometric)
+///     .is_ok());
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct AltitudeModels<'a> {
+    geoid: Option<&'a dyn Geoid>,
+    terrain: Option<&'a Terrain>,
+}
+
+impl<'a> AltitudeModels<'a> {
+    /// ジオイドモデルと地表面から [`AltitudeModels`] を生成します。
+    ///
+    /// 変換に用いないモデルは `None` とします。
+    pub fn new(geoid: Option<&'a dyn Geoid>, terrain: Option<&'a Terrain>) -> AltitudeModels<'a> {
+        AltitudeModels { geoid, terrain }
+    }
+
+    /// ジオイドモデルを返します。
+    pub fn as_geoid(&self) -> Option<&'a dyn Geoid> {
+        self.geoid
+    }
+
+    /// 地表面を返します。
+    pub fn as_terrain(&self) -> Option<&'a Terrain> {
+        self.terrain
+    }
+
+    /// 高度の基準が `from` である座標を、基準が `to` の座標へ変換します。
+    ///
+    /// 対地高度と楕円体高の変換は、標高を経由して行います。
+    ///
+    /// # 戻り値
+    /// * 変換に必要なモデルが与えられていない場合は [`Error::AltitudeModelMissing`] を返します
+    /// * モデルから値を求められない地点の場合は [`Error::GridOutOfRange`] を返します
+    /// * 変換後の高度が範囲外の場合は [`Error::AltitudeOutOfRange`] を返します
+    pub fn convert(
+        &self,
+        coordinate: &Coordinate,
+        from: AltitudeReference,
+        to: AltitudeReference,
+    ) -> Result<Coordinate, Error> {
+        if from == to {
+            return Ok(*coordinate);
+        }
+        let missing = || Error::AltitudeModelMissing { from, to };
+        let uses = |reference: AltitudeReference| from == reference || to == reference;
+        if uses(AltitudeReference::Ellipsoidal) && self.geoid.is_none()
+            || uses(AltitudeReference::Agl) && self.terrain.is_none()
+        {
+            return Err(missing());
+        }
+        let geoid = || self.geoid.ok_or_else(missing);
+        let terrain = || self.terrain.ok_or_else(missing);
+
+        // 標高へ変換してから、目的の基準へ変換する
+        let orthometric = match from {
+            AltitudeReference::Orthometric => *coordinate,
+            AltitudeReference::Ellipsoidal => coordinate.ellipsoidal_to_orthometric(geoid()?)?,
+            AltitudeReference::Agl => coordinate.agl_to_msl(terrain()?)?,
+        };
+        match to {
+            AltitudeReference::Orthometric => Ok(orthometric),
+            AltitudeReference::Ellipsoidal => orthometric.orthometric_to_ellipsoidal(geoid()?),
+            AltitudeReference::Agl => orthometric.msl_to_agl(terrain()?),
+        }
+    }
+}
+
+impl fmt::Debug for AltitudeModels<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AltitudeModels")
+            .field("geoid", &self.geoid.map(|_| ".."))
+            .field("terrain", &self.terrain)
+            .finish()
+    }
+}
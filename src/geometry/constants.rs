@@ -28,3 +28,20 @@ pub const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
 /// `e² = 1 - (b² / a²)`
 ///
 pub const WGS84_E2: f64 = 1.0 - (WGS84_B * WGS84_B) / (WGS84_A * WGS84_A);
+
+/// GRS80 楕円体の長半径（赤道半径）[m]
+///
+/// 日本の測地成果 JGD2000 および JGD2011 が準拠する楕円体。
+/// 長半径は WGS-84 と等しく、扁平率のみがわずかに異なる。
+pub const GRS80_A: f64 = 6_378_137.0;
+
+/// GRS80 楕円体の逆扁平率（1 / f）
+pub const GRS80_INV_F: f64 = 298.257_222_101;
+
+/// Bessel 1841 楕円体の長半径（赤道半径）[m]
+///
+/// 旧日本測地系（Tokyo Datum）が準拠する楕円体。
+pub const BESSEL_A: f64 = 6_377_397.155;
+
+/// Bessel 1841 楕円体の逆扁平率（1 / f）
+pub const BESSEL_INV_F: f64 = 299.152_813;
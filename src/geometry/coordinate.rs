@@ -3,8 +3,12 @@ use std::fmt;
 use crate::{
     error::Error,
     geometry::{
-        constants::{WGS84_A, WGS84_E2},
+        altitude::{AltitudeModels, AltitudeReference},
         ecef::Ecef,
+        ellipsoid::Ellipsoid,
+        geodesic::GeodesicInverse,
+        geoid::Geoid,
+        terrain::Terrain,
     },
     spatial_id::single::SingleId,
 };
@@ -203,6 +207,8 @@ impl Coordinate {
     ///
     /// 緯度・経度・高度をそれぞれ空間 ID の各成分（`x`, `y`, `f`）へ変換し、
     /// ズームレベル `z` を含む [SingleId] を生成します。
+    /// 高度は楕円体高として扱います。標高や対地高度の座標は
+    /// [`Coordinate::to_single_id_with_reference`] で変換します。
     ///
     /// # 引数
     /// * `z` - 空間 ID のズームレベル
@@ -237,6 +243,52 @@ impl Coordinate {
         unsafe { SingleId::uncheck_new(z, f, x, y) }
     }
 
+    /// 高度の基準が `reference` であるこの座標を楕円体高に変換し、
+    /// 指定されたズームレベルの [`SingleId`] へ変換します。
+    ///
+    /// [`Coordinate::to_single_id`] は高度を楕円体高として扱います。標高や対地高度で表された
+    /// 座標はこの関数を用いて変換します。
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::altitude::{AltitudeModels, AltitudeReference};
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::geoid::GridGeoid;
+    /// let geoid = GridGeoid::from_gsigeo_str(
+    ///     "35.0 139.0 0.5 0.5 2 2 1 ver2.2\n36.0 36.0\n36.0 36.0\n",
+    /// )
+    /// .unwrap();
+    /// let models = AltitudeModels::new(Some(&geoid), None);
+    ///
+    /// let orthometric = Coordinate::new(35.2, 139.2, 10.0).unwrap();
+    /// let id = orthometric
+    ///     .to_single_id_with_reference(25, AltitudeReference::Orthometric, &models)
+    ///     .unwrap();
+    /// assert_eq!(id.as_f(), 46);
+    ///
+    /// // 楕円体高として扱う場合はモデルを必要としない
+    /// let ellipsoidal = orthometric
+    ///     .to_single_id_with_reference(25, AltitudeReference::Ellipsoidal, &AltitudeModels::default())
+    ///     .unwrap();
+    /// assert_eq!(ellipsoidal, orthometric.to_single_id(25));
+    ///
+    /// let missing = orthometric.to_single_id_with_reference(
+    ///     25,
+    ///     AltitudeReference::Orthometric,
+    ///     &AltitudeModels::default(),
+    /// );
+    /// assert!(matches!(missing, Err(Error::AltitudeModelMissing { .. })));
+    /// ```
+    pub fn to_single_id_with_reference(
+        &self,
+        z: u8,
+        reference: AltitudeReference,
+        models: &AltitudeModels,
+    ) -> Result<SingleId, Error> {
+        let ellipsoidal = models.convert(self, reference, AltitudeReference::Ellipsoidal)?;
+        Ok(ellipsoidal.to_single_id(z))
+    }
+
     /// 他の [`Coordinate`] との距離をメートル単位で返します。
     ///
     /// この関数は、両座標を地心直交座標系（ECEF）へ変換したうえで、
@@ -263,6 +315,177 @@ impl Coordinate {
         let e2: Ecef = (*other).into();
         e1.distance(&e2)
     }
+
+    /// 指定された楕円体を基準として、この座標を [`Ecef`] に変換します。
+    ///
+    /// `From<Coordinate> for Ecef` は WGS-84 を基準とした変換です。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::{coordinate::Coordinate, ecef::Ecef, ellipsoid::Ellipsoid};
+    /// let coord = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+    ///
+    /// // GRS80 と WGS-84 の差は 1mm に満たない
+    /// let wgs84: Ecef = coord.into();
+    /// assert!(coord.to_ecef(&Ellipsoid::GRS80).distance(&wgs84) < 1e-3);
+    ///
+    /// // Bessel 楕円体とは数百メートル異なる
+    /// assert!(coord.to_ecef(&Ellipsoid::BESSEL_1841).distance(&wgs84) > 100.0);
+    /// ```
+    pub fn to_ecef(self, ellipsoid: &Ellipsoid) -> Ecef {
+        ellipsoid.geodetic_to_ecef([self.latitude, self.longitude, self.altitude])
+    }
+
+    /// 他の [`Coordinate`] までの、WGS-84 楕円体面上の測地線長をメートル単位で返します。
+    ///
+    /// [`Coordinate::distance`] が 2 点を結ぶ直線の長さを返すのに対し、この関数は
+    /// 楕円体面に沿った最短経路の長さを返します。高度は考慮しません。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// let coord_tokyo = Coordinate::new(35.681382, 139.76608399999998, 0.0).unwrap();
+    /// let coord_osaka = Coordinate::new(34.702485, 135.495951, 0.0).unwrap();
+    ///
+    /// let surface = coord_tokyo.geodesic_distance(&coord_osaka);
+    /// assert!((surface - 403_739.4).abs() < 0.1);
+    /// assert!(surface > coord_tokyo.distance(&coord_osaka));
+    /// ```
+    pub fn geodesic_distance(&self, other: &Coordinate) -> f64 {
+        self.geodesic_inverse(other).as_distance()
+    }
+
+    /// 他の [`Coordinate`] までの測地線長と、両端における方位角を返します。
+    ///
+    /// 方位角は北から時計回りの度で表されます。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// let origin = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+    /// let north = Coordinate::new(35.1, 139.0, 0.0).unwrap();
+    ///
+    /// let inverse = origin.geodesic_inverse(&north);
+    /// assert!(inverse.as_azimuth1().abs() < 1e-9);
+    /// assert!((inverse.as_distance() - 11_095.0).abs() < 1.0);
+    /// ```
+    pub fn geodesic_inverse(&self, other: &Coordinate) -> GeodesicInverse {
+        Ellipsoid::WGS84.geodesic().inverse(
+            self.latitude,
+            self.longitude,
+            other.latitude,
+            other.longitude,
+        )
+    }
+
+    /// この座標から方位角 `azimuth`（度）の向きへ、WGS-84 楕円体面に沿って
+    /// `distance`（m）だけ進んだ地点を返します。
+    ///
+    /// 高度はこの座標の値を引き継ぎます。
+    ///
+    /// # 戻り値
+    /// * 到達した地点が空間 ID 上で扱える範囲内にある場合は `Ok(Coordinate)` を返します
+    /// * 範囲外の緯度に到達した場合は [`Error::LatitudeOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// let origin = Coordinate::new(35.0, 139.0, 100.0).unwrap();
+    ///
+    /// // 北東へ 5km 進み、始点までの距離を測り直す
+    /// let destination = origin.destination(45.0, 5_000.0).unwrap();
+    /// assert!((origin.geodesic_distance(&destination) - 5_000.0).abs() < 1e-6);
+    /// assert_eq!(destination.as_altitude(), 100.0);
+    ///
+    /// // 北極へ向かう場合は範囲外となる
+    /// assert!(origin.destination(0.0, 6_000_000.0).is_err());
+    /// ```
+    pub fn destination(&self, azimuth: f64, distance: f64) -> Result<Coordinate, Error> {
+        let direct =
+            Ellipsoid::WGS84
+                .geodesic()
+                .direct(self.latitude, self.longitude, azimuth, distance);
+        Coordinate::new(direct.as_latitude(), direct.as_longitude(), self.altitude)
+    }
+
+    /// 高度を標高（ジオイドからの高さ）とみなし、ジオイド高を加えて楕円体高に変換した座標を返します。
+    ///
+    /// [`Ecef`] への変換などは高度を楕円体高として扱うため、標高で表されたデータは
+    /// この関数で変換してから使用します。
+    ///
+    /// # 戻り値
+    /// * ジオイド高を求められない場合は、[`Geoid::undulation`] が返す `Error` を返します
+    /// * 変換後の高度が範囲外の場合は [`Error::AltitudeOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::geoid::GridGeoid;
+    /// let geoid = GridGeoid::from_gsigeo_str(
+    ///     "35.0 139.0 0.5 0.5 2 2 1 ver2.2\n36.0 37.0\n38.0 39.0\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// // 標高 10m の地点のジオイド高は 37.5m
+    /// let above_sea = Coordinate::new(35.25, 139.25, 10.0).unwrap();
+    /// let ellipsoidal = above_sea.orthometric_to_ellipsoidal(&geoid).unwrap();
+    /// assert!((ellipsoidal.as_altitude() - 47.5).abs() < 1e-9);
+    ///
+    /// // z=25 では F インデックスが 37 以上ずれる
+    /// let f = |c: Coordinate| c.to_single_id(25).as_f();
+    /// assert!(f(ellipsoidal) - f(above_sea) >= 37);
+    ///
+    /// let back = ellipsoidal.ellipsoidal_to_orthometric(&geoid).unwrap();
+    /// assert!((back.as_altitude() - 10.0).abs() < 1e-9);
+    /// ```
+    pub fn orthometric_to_ellipsoidal<G: Geoid + ?Sized>(
+        &self,
+        geoid: &G,
+    ) -> Result<Coordinate, Error> {
+        let undulation = geoid.undulation(self.latitude, self.longitude)?;
+        Coordinate::new(self.latitude, self.longitude, self.altitude + undulation)
+    }
+
+    /// 高度を楕円体高とみなし、ジオイド高を引いて標高に変換した座標を返します。
+    ///
+    /// [`Coordinate::orthometric_to_ellipsoidal`] の逆変換です。
+    pub fn ellipsoidal_to_orthometric<G: Geoid + ?Sized>(
+        &self,
+        geoid: &G,
+    ) -> Result<Coordinate, Error> {
+        let undulation = geoid.undulation(self.latitude, self.longitude)?;
+        Coordinate::new(self.latitude, self.longitude, self.altitude - undulation)
+    }
+
+    /// 高度を対地高度とみなし、地表面の標高を加えて標高に変換した座標を返します。
+    ///
+    /// # 戻り値
+    /// * 地表面の標高を求められない場合は [`Error::GridOutOfRange`] を返します
+    /// * 変換後の高度が範囲外の場合は [`Error::AltitudeOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::terrain::Terrain;
+    /// let terrain = Terrain::from_esri_ascii_str(
+    ///     "ncols 2\nnrows 2\nxllcorner 139.0\nyllcorner 35.0\ncellsize 0.01\n40 40\n40 40\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// // 地表から 150m の高さは標高 190m
+    /// let agl = Coordinate::new(35.005, 139.005, 150.0).unwrap();
+    /// let msl = agl.agl_to_msl(&terrain).unwrap();
+    /// assert!((msl.as_altitude() - 190.0).abs() < 1e-9);
+    ///
+    /// let back = msl.msl_to_agl(&terrain).unwrap();
+    /// assert!((back.as_altitude() - 150.0).abs() < 1e-9);
+    /// ```
+    pub fn agl_to_msl(&self, terrain: &Terrain) -> Result<Coordinate, Error> {
+        let ground = terrain.elevation(self)?;
+        Coordinate::new(self.latitude, self.longitude, self.altitude + ground)
+    }
+
+    /// 高度を標高とみなし、地表面の標高を引いて対地高度に変換した座標を返します。
+    ///
+    /// [`Coordinate::agl_to_msl`] の逆変換です。
+    pub fn msl_to_agl(&self, terrain: &Terrain) -> Result<Coordinate, Error> {
+        let ground = terrain.elevation(self)?;
+        Coordinate::new(self.latitude, self.longitude, self.altitude - ground)
+    }
 }
 
 impl From<Coordinate> for Ecef {
@@ -278,22 +501,7 @@ impl From<Coordinate> for Ecef {
     /// ```
 
     fn from(value: Coordinate) -> Self {
-        let lat = value.latitude.to_radians();
-        let lon = value.longitude.to_radians();
-        let h = value.altitude;
-
-        let sin_lat = lat.sin();
-        let cos_lat = lat.cos();
-        let sin_lon = lon.sin();
-        let cos_lon = lon.cos();
-
-        let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
-
-        let x = (n + h) * cos_lat * cos_lon;
-        let y = (n + h) * cos_lat * sin_lon;
-        let z = (n * (1.0 - WGS84_E2) + h) * sin_lat;
-
-        Ecef::new(x, y, z)
+        value.to_ecef(&Ellipsoid::WGS84)
     }
 }
 
@@ -0,0 +1,336 @@
+//! 測地系の間の座標変換
+//!
+//! 各測地系の座標を、その測地系が準拠する楕円体で [`Ecef`](crate::geometry::ecef::Ecef)
+//! に変換したうえで、7 パラメータの Helmert 変換により WGS-84 を経由して変換します。
+//! 地域ごとのひずみを含む旧日本測地系の座標をより正確に変換する場合は、TKY2JGD 形式の
+//! 補正パラメータファイルを [`GridCorrection`](crate::geometry::datum::GridCorrection)
+//! として読み込んで使用します。
+
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    error::Error,
+    geometry::{coordinate::Coordinate, ecef::Ecef, ellipsoid::Ellipsoid, helpers::read_grid_file},
+};
+
+/// 測地系
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Datum {
+    /// 旧日本測地系（Bessel 1841 楕円体）
+    Tokyo,
+    /// 日本測地系 2000（GRS80 楕円体）
+    Jgd2000,
+    /// 日本測地系 2011（GRS80 楕円体）
+    Jgd2011,
+    /// WGS-84
+    #[default]
+    Wgs84,
+}
+
+impl Datum {
+    /// 測地系が準拠する楕円体を返します。
+    pub fn ellipsoid(self) -> Ellipsoid {
+        match self {
+            Datum::Tokyo => Ellipsoid::BESSEL_1841,
+            Datum::Jgd2000 | Datum::Jgd2011 => Ellipsoid::GRS80,
+            Datum::Wgs84 => Ellipsoid::WGS84,
+        }
+    }
+
+    /// この測地系の [`Ecef`] を WGS-84 の [`Ecef`] へ変換する Helmert 変換を返します。
+    ///
+    /// JGD2000 と JGD2011 は ITRF に準拠しており、WGS-84 と同一とみなします。
+    pub fn helmert_to_wgs84(self) -> Helmert {
+        match self {
+            Datum::Tokyo => Helmert::TOKYO_TO_JGD2000,
+            Datum::Jgd2000 | Datum::Jgd2011 | Datum::Wgs84 => Helmert::IDENTITY,
+        }
+    }
+}
+
+/// 7 パラメータの Helmert 変換（位置ベクトル変換）
+///
+/// 平行移動 `T`（m）、各軸まわりの回転 `R`（秒）、縮尺の補正 `s`（ppm）により、
+/// `X' = T + (1 + s)·R·X` として [`Ecef`] を変換します。
+/// 回転は微小であるものとして線形化した回転行列を用います。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Helmert {
+    translation: [f64; 3],
+    rotation: [f64; 3],
+    scale: f64,
+}
+
+impl Helmert {
+    /// 恒等変換
+    pub const IDENTITY: Helmert = Helmert {
+        translation: [0.0; 3],
+        rotation: [0.0; 3],
+        scale: 0.0,
+    };
+
+    /// 旧日本測地系から JGD2000 への変換（国土地理院による 3 パラメータ）
+    pub const TOKYO_TO_JGD2000: Helmert = Helmert {
+        translation: [-146.414, 507.337, 680.507],
+        rotation: [0.0; 3],
+        scale: 0.0,
+    };
+
+    /// 平行移動（m）、各軸まわりの回転（秒）、縮尺の補正（ppm）から [`Helmert`] を生成します。
+    pub fn new(translation: [f64; 3], rotation: [f64; 3], scale: f64) -> Helmert {
+        Helmert {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// 平行移動（m）を返します。
+    pub fn as_translation(&self) -> [f64; 3] {
+        self.translation
+    }
+
+    /// 各軸まわりの回転（秒）を返します。
+    pub fn as_rotation(&self) -> [f64; 3] {
+        self.rotation
+    }
+
+    /// 縮尺の補正（ppm）を返します。
+    pub fn as_scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// 逆変換を返します。
+    ///
+    /// 各パラメータの符号を反転した近似であり、誤差は回転と縮尺の積の程度に収まります。
+    pub fn inverse(&self) -> Helmert {
+        Helmert::new(
+            self.translation.map(|v| -v),
+            self.rotation.map(|v| -v),
+            -self.scale,
+        )
+    }
+
+    /// [`Ecef`] を変換します。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::datum::Helmert;
+    /// # use kasane_logic::geometry::ecef::Ecef;
+    /// let p = Ecef::new(-3_959_340.0, 3_352_854.0, 3_697_471.0);
+    /// let q = Helmert::TOKYO_TO_JGD2000.apply(&p);
+    /// assert!((q.as_x() - (p.as_x() - 146.414)).abs() < 1e-6);
+    ///
+    /// // 逆変換で元に戻る
+    /// let back = Helmert::TOKYO_TO_JGD2000.inverse().apply(&q);
+    /// assert!(back.distance(&p) < 1e-6);
+    /// ```
+    pub fn apply(&self, point: &Ecef) -> Ecef {
+        let [tx, ty, tz] = self.translation;
+        let [rx, ry, rz] = self.rotation.map(|v| (v / 3600.0).to_radians());
+        let s = 1.0 + self.scale * 1e-6;
+        let (x, y, z) = (point.as_x(), point.as_y(), point.as_z());
+        Ecef::new(
+            tx + s * (x - rz * y + ry * z),
+            ty + s * (rz * x + y - rx * z),
+            tz + s * (-ry * x + rx * y + z),
+        )
+    }
+}
+
+/// 測地系 `from` の座標を、測地系 `to` の座標へ Helmert 変換により変換する。
+///
+/// 高度は各測地系の楕円体からの高さとして扱う。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::datum::{transform, Datum};
+/// // 旧日本測地系の東京駅付近の座標
+/// let tokyo = Coordinate::new(35.678_132, 139.769_650, 0.0).unwrap();
+/// let wgs84 = transform(&tokyo, Datum::Tokyo, Datum::Wgs84).unwrap();
+///
+/// // 北西へ約 450m ずれる
+/// let shift = tokyo.geodesic_distance(&wgs84);
+/// assert!(400.0 < shift && shift < 500.0);
+/// assert!(wgs84.as_latitude() > tokyo.as_latitude());
+/// assert!(wgs84.as_longitude() < tokyo.as_longitude());
+///
+/// // 逆向きに変換すると元に戻る
+/// let back = transform(&wgs84, Datum::Wgs84, Datum::Tokyo).unwrap();
+/// assert!(back.geodesic_distance(&tokyo) < 1e-3);
+/// ```
+pub fn transform(coordinate: &Coordinate, from: Datum, to: Datum) -> Result<Coordinate, Error> {
+    let source = coordinate.to_ecef(&from.ellipsoid());
+    let wgs84 = from.helmert_to_wgs84().apply(&source);
+    let target = to.helmert_to_wgs84().inverse().apply(&wgs84);
+    target.to_coordinate(&to.ellipsoid())
+}
+
+/// 3 次メッシュ（緯度 30 秒・経度 45 秒）の格子点ごとに緯度・経度の補正量を持つ補正パラメータ
+///
+/// 国土地理院の TKY2JGD（旧日本測地系から JGD2000）や PatchJGD（JGD2000 から JGD2011）の
+/// `.par` ファイルと同じ形式で、各行にメッシュコードと、そのメッシュの南西端における
+/// 緯度・経度の補正量（秒）を記述します。格子点の間は双一次補間します。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::datum::GridCorrection;
+/// let par = "\
+/// JGD2000-TokyoDatum Ver.2.1.1
+/// MeshCode   dB(sec)   dL(sec)
+/// 53394610  11.60000 -11.80000
+/// 53394611  11.60000 -11.90000
+/// 53394620  11.70000 -11.80000
+/// 53394621  11.70000 -11.90000
+/// ";
+/// let grid = GridCorrection::from_par_str(par).unwrap();
+/// assert_eq!(grid.len(), 4);
+///
+/// // 4 つの格子点の中央
+/// let tokyo = Coordinate::new(35.675 + 1.0 / 240.0, 139.75 + 1.0 / 160.0, 0.0).unwrap();
+/// let [dlat, dlon] = grid.shift(tokyo.as_latitude(), tokyo.as_longitude()).unwrap();
+/// assert!((dlat * 3600.0 - 11.65).abs() < 1e-9);
+/// assert!((dlon * 3600.0 + 11.85).abs() < 1e-9);
+///
+/// let jgd2000 = grid.apply(&tokyo).unwrap();
+/// let back = grid.apply_inverse(&jgd2000).unwrap();
+/// assert!((back.as_latitude() - tokyo.as_latitude()).abs() < 1e-12);
+///
+/// // 格子の範囲外
+/// let osaka = Coordinate::new(34.7, 135.5, 0.0).unwrap();
+/// assert!(grid.apply(&osaka).is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GridCorrection {
+    /// 格子点の（緯度方向, 経度方向）のインデックスと、緯度・経度の補正量（秒）
+    nodes: HashMap<(i64, i64), [f64; 2]>,
+}
+
+/// 3 次メッシュの緯度方向の間隔（度）の逆数
+const LATITUDE_DIVISIONS: f64 = 120.0;
+/// 3 次メッシュの経度方向の間隔（度）の逆数
+const LONGITUDE_DIVISIONS: f64 = 80.0;
+
+impl GridCorrection {
+    /// `.par` 形式の補正パラメータファイルを読み込みます。
+    ///
+    /// # 戻り値
+    /// * ファイルを読み込めない場合は [`Error::GridFileRead`] を返します
+    /// * 解釈できない行がある場合は [`Error::GridFileParse`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::datum::GridCorrection;
+    /// let missing = GridCorrection::from_par_file("/nonexistent/TKY2JGD.par");
+    /// assert!(matches!(missing, Err(Error::GridFileRead { .. })));
+    ///
+    /// let broken = GridCorrection::from_par_str("MeshCode dB dL\n53394610 11.6 -11.8\n5339461 x\n");
+    /// assert_eq!(broken.unwrap_err(), Error::GridFileParse { line: 3 });
+    /// ```
+    pub fn from_par_file(path: impl AsRef<Path>) -> Result<GridCorrection, Error> {
+        GridCorrection::from_par_str(&read_grid_file(path.as_ref())?)
+    }
+
+    /// `.par` 形式の文字列から補正パラメータを読み込みます。
+    ///
+    /// 最初の格子点の行より前の行は見出しとして読み飛ばします。
+    /// 4 列目以降（PatchJGD の高さの補正量など）は無視します。
+    pub fn from_par_str(text: &str) -> Result<GridCorrection, Error> {
+        let mut nodes = HashMap::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_par_line(line) {
+                Some((node, shift)) => {
+                    nodes.insert(node, shift);
+                }
+                None if nodes.is_empty() => continue,
+                None => return Err(Error::GridFileParse { line: index + 1 }),
+            }
+        }
+        Ok(GridCorrection { nodes })
+    }
+
+    /// 格子点の個数を返します。
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 格子点を持たない場合に `true` を返します。
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 緯度・経度（度）における緯度・経度の補正量（度）を、周囲の格子点から双一次補間して返します。
+    ///
+    /// 周囲の 4 つの格子点のいずれかが存在しない場合は [`Error::GridOutOfRange`] を返します。
+    pub fn shift(&self, latitude: f64, longitude: f64) -> Result<[f64; 2], Error> {
+        let u = latitude * LATITUDE_DIVISIONS;
+        let v = longitude * LONGITUDE_DIVISIONS;
+        let (i, j) = (u.floor() as i64, v.floor() as i64);
+        let (s, t) = (u - u.floor(), v - v.floor());
+
+        let node = |di: i64, dj: i64| {
+            self.nodes
+                .get(&(i + di, j + dj))
+                .copied()
+                .ok_or(Error::GridOutOfRange {
+                    latitude,
+                    longitude,
+                })
+        };
+        let [sw, se, nw, ne] = [node(0, 0)?, node(0, 1)?, node(1, 0)?, node(1, 1)?];
+
+        Ok(std::array::from_fn(|k| {
+            let south = sw[k] + (se[k] - sw[k]) * t;
+            let north = nw[k] + (ne[k] - nw[k]) * t;
+            (south + (north - south) * s) / 3600.0
+        }))
+    }
+
+    /// 補正量を加えた座標を返します。高度は変更しません。
+    pub fn apply(&self, coordinate: &Coordinate) -> Result<Coordinate, Error> {
+        let (lat, lon) = (coordinate.as_latitude(), coordinate.as_longitude());
+        let [dlat, dlon] = self.shift(lat, lon)?;
+        Coordinate::new(lat + dlat, lon + dlon, coordinate.as_altitude())
+    }
+
+    /// [`GridCorrection::apply`] の逆変換を、補正後の座標での補正量を用いた反復計算で求めます。
+    pub fn apply_inverse(&self, coordinate: &Coordinate) -> Result<Coordinate, Error> {
+        let (lat, lon) = (coordinate.as_latitude(), coordinate.as_longitude());
+        let (mut source_lat, mut source_lon) = (lat, lon);
+        for _ in 0..8 {
+            let [dlat, dlon] = self.shift(source_lat, source_lon)?;
+            let next = (lat - dlat, lon - dlon);
+            let converged =
+                (next.0 - source_lat).abs() < 1e-14 && (next.1 - source_lon).abs() < 1e-14;
+            (source_lat, source_lon) = next;
+            if converged {
+                break;
+            }
+        }
+        Coordinate::new(source_lat, source_lon, coordinate.as_altitude())
+    }
+}
+
+/// `.par` ファイルの 1 行を、格子点のインデックスと補正量に変換する。
+fn parse_par_line(line: &str) -> Option<((i64, i64), [f64; 2])> {
+    let mut fields = line.split_whitespace();
+    let code = fields.next()?;
+    if code.len() != 8 {
+        return None;
+    }
+    let code: i64 = code.parse().ok()?;
+    let dlat: f64 = fields.next()?.parse().ok()?;
+    let dlon: f64 = fields.next()?.parse().ok()?;
+
+    // メッシュコード pp qq r s t u（1 次・2 次・3 次メッシュの緯度方向と経度方向の番号）
+    let digit = |position: u32| (code / 10_i64.pow(position)) % 10;
+    let (p, q) = (code / 1_000_000, (code / 10_000) % 100);
+    let (r, s, t, u) = (digit(3), digit(2), digit(1), digit(0));
+    if r >= 8 || s >= 8 {
+        return None;
+    }
+    let i = p * 80 + r * 10 + t;
+    let j = (q + 100) * 80 + s * 10 + u;
+    Some(((i, j), [dlat, dlon]))
+}
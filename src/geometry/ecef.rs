@@ -1,11 +1,11 @@
-use std::fmt;
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
 
 use crate::{
     error::Error,
-    geometry::{
-        constants::{WGS84_A, WGS84_E2, WGS84_F},
-        coordinate::Coordinate,
-    },
+    geometry::{coordinate::Coordinate, ellipsoid::Ellipsoid, geodetic::Geodetic},
     spatial_id::single::SingleId,
 };
 
@@ -135,6 +135,18 @@ impl Ecef {
     }
 
     /// この ECEF 座標を、指定されたズームレベルの [`SingleId`] に変換します。
+    ///
+    /// WGS-84 を基準とし、変換後の緯度が ±85.0511 度を超える場合は [`Error::LatitudeOutOfRange`] を
+    /// 返します。極付近の点を範囲内に丸めて扱う場合は、[`Geodetic::to_coordinate_clamped`] を経由します。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::{ecef::Ecef, geodetic::Geodetic};
+    /// let near_pole = Ecef::new(1_000.0, 0.0, 6_356_752.0);
+    /// assert!(near_pole.to_single_id(20).is_err());
+    ///
+    /// let clamped = Geodetic::from(near_pole).to_coordinate_clamped().unwrap();
+    /// assert_eq!(clamped.to_single_id(20).as_y(), 0);
+    /// ```
     pub fn to_single_id(&self, z: u8) -> Result<SingleId, Error> {
         let coordinate: Coordinate = (*self).try_into()?;
         Ok(coordinate.to_single_id(z))
@@ -157,40 +169,168 @@ impl Ecef {
             + (self.as_z() - other.as_z()).powi(2))
         .sqrt()
     }
-}
 
-impl TryFrom<Ecef> for Coordinate {
-    type Error = Error;
-    /// 地心直交座標系（ECEF）から地理座標（緯度・経度・高度）への変換を提供します。
+    /// 原点からのベクトルとみなした内積を返します。
     ///
-    /// この変換は WGS-84 楕円体モデルに基づいており、
-    /// Bowring 法による反復計算を用いて緯度と高度を求めます。
-    fn try_from(value: Ecef) -> Result<Self, Self::Error> {
-        let x = value.x;
-        let y = value.y;
-        let z = value.z;
+    /// # Examples
+    /// ```
+    /// use kasane_logic::geometry::ecef::Ecef;
+    ///
+    /// let a = Ecef::new(1.0, 2.0, 3.0);
+    /// let b = Ecef::new(4.0, -5.0, 6.0);
+    ///
+    /// assert_eq!(a.dot(&b), 12.0);
+    /// ```
+    pub fn dot(&self, other: &Ecef) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
 
-        let lon = y.atan2(x);
-        let p = (x * x + y * y).sqrt();
+    /// 原点からのベクトルとみなした外積を返します。
+    ///
+    /// # Examples
+    /// ```
+    /// use kasane_logic::geometry::ecef::Ecef;
+    ///
+    /// let x = Ecef::new(1.0, 0.0, 0.0);
+    /// let y = Ecef::new(0.0, 1.0, 0.0);
+    ///
+    /// assert_eq!(x.cross(&y).as_z(), 1.0);
+    /// ```
+    pub fn cross(&self, other: &Ecef) -> Ecef {
+        Ecef::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
 
-        // 緯度の初期値（Bowring）
-        let mut lat = (z / p).atan2(1.0 - WGS84_F);
-        let mut h = 0.0;
+    /// 原点からのベクトルとみなした長さを返します。
+    ///
+    /// # Examples
+    /// ```
+    /// use kasane_logic::geometry::ecef::Ecef;
+    ///
+    /// assert_eq!(Ecef::new(3.0, 4.0, 0.0).norm(), 5.0);
+    /// ```
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
 
-        for _ in 0..10 {
-            let sin_lat = lat.sin();
-            let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
-            h = p / lat.cos() - n;
+    /// 範囲の検証を行わずに、緯度・経度（度）・高度（m）の配列 `[lat, lon, h]` から変換する。
+    pub(crate) fn from_geodetic_unchecked(geodetic: [f64; 3]) -> Ecef {
+        Ellipsoid::WGS84.geodetic_to_ecef(geodetic)
+    }
 
-            let new_lat = (z + WGS84_E2 * n * sin_lat).atan2(p);
+    /// 範囲の検証を行わずに、WGS-84 の緯度・経度（度）・高度（m）の配列 `[lat, lon, h]` へ変換する。
+    ///
+    /// WEBメルカトルの緯度制限を超える点もそのまま返すため、経路の補間など
+    /// 中間計算での利用を想定している。
+    pub(crate) fn to_geodetic_unchecked(self) -> [f64; 3] {
+        Ellipsoid::WGS84.ecef_to_geodetic(self)
+    }
 
-            if (new_lat - lat).abs() < 1e-12 {
-                lat = new_lat;
-                break;
-            }
-            lat = new_lat;
-        }
+    /// 指定された楕円体を基準として、この ECEF 座標を [`Coordinate`] に変換します。
+    ///
+    /// `TryFrom<Ecef> for Coordinate` は WGS-84 を基準とした変換です。
+    ///
+    /// # 戻り値
+    /// * 変換後の座標が空間 ID 上で扱える範囲内にある場合は `Ok(Coordinate)` を返します
+    /// * 範囲外の場合は、対応する `Error` を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::{coordinate::Coordinate, ecef::Ecef, ellipsoid::Ellipsoid};
+    /// let coord = Coordinate::new(35.0, 139.0, 10.0).unwrap();
+    /// let ecef = coord.to_ecef(&Ellipsoid::BESSEL_1841);
+    ///
+    /// let back = ecef.to_coordinate(&Ellipsoid::BESSEL_1841).unwrap();
+    /// assert!((back.as_latitude() - 35.0).abs() < 1e-9);
+    /// assert!((back.as_altitude() - 10.0).abs() < 1e-3);
+    ///
+    /// // 別の楕円体で解釈すると、高度が数百メートル異なる
+    /// let wgs84: Coordinate = ecef.try_into().unwrap();
+    /// assert!((wgs84.as_altitude() - 10.0).abs() > 100.0);
+    /// ```
+    pub fn to_coordinate(self, ellipsoid: &Ellipsoid) -> Result<Coordinate, Error> {
+        self.to_geodetic(ellipsoid).try_into()
+    }
+
+    /// 指定された楕円体を基準として、この ECEF 座標を [`Geodetic`] に変換します。
+    ///
+    /// 閉形式の解を用いるため反復の収束に依存せず、極や地球の中心付近を含むすべての点で
+    /// 1μm 未満の誤差で求まります。緯度の範囲は制限しません。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::{coordinate::Coordinate, ecef::Ecef, ellipsoid::Ellipsoid};
+    /// let coord = Coordinate::new(35.0, 139.0, 8_000.0).unwrap();
+    /// let ecef = coord.to_ecef(&Ellipsoid::GRS80);
+    ///
+    /// let geodetic = ecef.to_geodetic(&Ellipsoid::GRS80);
+    /// assert!((geodetic.as_latitude() - 35.0).abs() < 1e-12);
+    /// assert!((geodetic.as_altitude() - 8_000.0).abs() < 1e-6);
+    /// ```
+    pub fn to_geodetic(self, ellipsoid: &Ellipsoid) -> Geodetic {
+        Geodetic::from_unchecked(ellipsoid.ecef_to_geodetic(self))
+    }
+}
+
+/// 成分ごとの和を返します。点に変位ベクトルを加える場合に使用します。
+///
+/// ```
+/// use kasane_logic::geometry::ecef::Ecef;
+///
+/// let p = Ecef::new(1.0, 2.0, 3.0) + Ecef::new(10.0, 20.0, 30.0);
+/// assert_eq!((p.as_x(), p.as_y(), p.as_z()), (11.0, 22.0, 33.0));
+/// ```
+impl Add for Ecef {
+    type Output = Ecef;
+
+    fn add(self, rhs: Ecef) -> Ecef {
+        Ecef::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
 
-        Coordinate::new(lat.to_degrees(), lon.to_degrees(), h)
+/// 成分ごとの差を返します。2 点間の変位ベクトルを求める場合に使用します。
+///
+/// ```
+/// use kasane_logic::geometry::ecef::Ecef;
+///
+/// let d = Ecef::new(3.0, 4.0, 5.0) - Ecef::new(3.0, 0.0, 5.0);
+/// assert_eq!(d.norm(), 4.0);
+/// ```
+impl Sub for Ecef {
+    type Output = Ecef;
+
+    fn sub(self, rhs: Ecef) -> Ecef {
+        Ecef::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+/// 各成分をスカラー倍します。
+impl Mul<f64> for Ecef {
+    type Output = Ecef;
+
+    fn mul(self, rhs: f64) -> Ecef {
+        Ecef::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+/// 各成分の符号を反転します。
+impl Neg for Ecef {
+    type Output = Ecef;
+
+    fn neg(self) -> Ecef {
+        Ecef::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl TryFrom<Ecef> for Coordinate {
+    type Error = Error;
+    /// 地心直交座標系（ECEF）から地理座標（緯度・経度・高度）への変換を提供します。
+    ///
+    /// この変換は WGS-84 楕円体モデルに基づいており、閉形式の解により緯度と高度を求めます。
+    /// 緯度が ±85.0511 度を超える場合は `Error` を返します。範囲外の点も扱う場合は
+    /// [`Geodetic`] へ変換します。他の楕円体を基準とする場合は [`Ecef::to_coordinate`] を使用します。
+    fn try_from(value: Ecef) -> Result<Self, Self::Error> {
+        value.to_coordinate(&Ellipsoid::WGS84)
     }
 }
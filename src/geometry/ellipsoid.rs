@@ -0,0 +1,183 @@
+use crate::{
+    error::Error,
+    geometry::{
+        constants::{BESSEL_A, BESSEL_INV_F, GRS80_A, GRS80_INV_F, WGS84_A, WGS84_F},
+        ecef::Ecef,
+        geodesic::Geodesic,
+    },
+};
+
+/// 長半径と扁平率で定義された、地球を近似する回転楕円体
+///
+/// 緯度・経度・高度と [`Ecef`] との変換や測地線の計算は、この楕円体を基準として行います。
+/// 既定値は WGS-84 であり、[`Coordinate`](crate::geometry::coordinate::Coordinate) と
+/// [`Ecef`] の間の `From` / `TryFrom` による変換も WGS-84 を前提とします。
+///
+/// ```
+/// # use kasane_logic::geometry::ellipsoid::Ellipsoid;
+/// assert_eq!(Ellipsoid::default(), Ellipsoid::WGS84);
+///
+/// // GRS80 と WGS-84 の短半径の差は 0.1mm 程度
+/// assert!((Ellipsoid::GRS80.as_b() - Ellipsoid::WGS84.as_b()).abs() < 1e-3);
+/// assert!((Ellipsoid::BESSEL_1841.as_b() - 6_356_078.963).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipsoid {
+    a: f64,
+    f: f64,
+}
+
+impl Ellipsoid {
+    /// WGS-84 楕円体
+    pub const WGS84: Ellipsoid = Ellipsoid {
+        a: WGS84_A,
+        f: WGS84_F,
+    };
+
+    /// GRS80 楕円体（JGD2000・JGD2011）
+    pub const GRS80: Ellipsoid = Ellipsoid {
+        a: GRS80_A,
+        f: 1.0 / GRS80_INV_F,
+    };
+
+    /// Bessel 1841 楕円体（旧日本測地系）
+    pub const BESSEL_1841: Ellipsoid = Ellipsoid {
+        a: BESSEL_A,
+        f: 1.0 / BESSEL_INV_F,
+    };
+
+    /// 長半径 `a`（m）と扁平率 `f` から [`Ellipsoid`] を生成します。
+    ///
+    /// # 戻り値
+    /// * `a` が正で、`f` が `0.0..1.0` の範囲内にある場合は `Ok(Ellipsoid)` を返します
+    /// * それ以外の場合は [`Error::EllipsoidOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::ellipsoid::Ellipsoid;
+    /// // 国際楕円体（Hayford 1909）
+    /// let hayford = Ellipsoid::new(6_378_388.0, 1.0 / 297.0).unwrap();
+    /// assert_eq!(hayford.as_a(), 6_378_388.0);
+    ///
+    /// assert!(Ellipsoid::new(-1.0, 0.0).is_err());
+    /// assert!(Ellipsoid::new(6_378_137.0, -0.003).is_err());
+    /// ```
+    pub fn new(a: f64, f: f64) -> Result<Ellipsoid, Error> {
+        if !(a > 0.0 && a.is_finite() && (0.0..1.0).contains(&f)) {
+            return Err(Error::EllipsoidOutOfRange { a, f });
+        }
+        Ok(Ellipsoid { a, f })
+    }
+
+    /// 長半径（赤道半径）[m] を返します。
+    pub fn as_a(&self) -> f64 {
+        self.a
+    }
+
+    /// 扁平率を返します。
+    pub fn as_f(&self) -> f64 {
+        self.f
+    }
+
+    /// 短半径（極半径）[m] を返します。
+    pub fn as_b(&self) -> f64 {
+        self.a * (1.0 - self.f)
+    }
+
+    /// 第一離心率の二乗を返します。
+    pub fn as_e2(&self) -> f64 {
+        let b = self.as_b();
+        1.0 - (b * b) / (self.a * self.a)
+    }
+
+    /// この楕円体上の測地線を計算する [`Geodesic`] を返します。
+    pub fn geodesic(&self) -> Geodesic {
+        Geodesic::new(self.a, self.f)
+    }
+
+    /// 範囲の検証を行わずに、緯度・経度（度）・高度（m）の配列 `[lat, lon, h]` から変換する。
+    pub(crate) fn geodetic_to_ecef(&self, [lat, lon, h]: [f64; 3]) -> Ecef {
+        let e2 = self.as_e2();
+        let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = lon.to_radians().sin_cos();
+
+        let n = self.a / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+
+        let x = (n + h) * cos_lat * cos_lon;
+        let y = (n + h) * cos_lat * sin_lon;
+        let z = (n * (1.0 - e2) + h) * sin_lat;
+
+        Ecef::new(x, y, z)
+    }
+
+    /// 範囲の検証を行わずに、緯度・経度（度）・高度（m）の配列 `[lat, lon, h]` へ変換する。
+    ///
+    /// Vermeille (2011) の閉形式解に、Karney (GeographicLib) による桁落ちへの対策を加えて用いる。
+    /// 反復計算を行わず、極軸上や地球の中心付近を含むすべての点で 1μm 未満の誤差で求まる。
+    pub(crate) fn ecef_to_geodetic(&self, ecef: Ecef) -> [f64; 3] {
+        let (x, y, z) = (ecef.as_x(), ecef.as_y(), ecef.as_z());
+        let a = self.a;
+        let e2 = self.as_e2();
+        let e2m = 1.0 - e2;
+        let e4 = e2 * e2;
+
+        let r_xy = x.hypot(y);
+        let lon = y.atan2(x);
+        let distance = r_xy.hypot(z);
+
+        // 非常に遠方の点では楕円体を点とみなす（以降の計算でのオーバーフローを避ける）
+        if distance > 2.0 * a / f64::EPSILON {
+            return [z.atan2(r_xy).to_degrees(), lon.to_degrees(), distance];
+        }
+
+        let p = (r_xy / a).powi(2);
+        let q = e2m * (z / a).powi(2);
+        let r = (p + q - e4) / 6.0;
+
+        let (sin_lat, cos_lat, h) = if e4 * q != 0.0 || r > 0.0 {
+            let s = e4 * p * q / 4.0;
+            let r2 = r * r;
+            let r3 = r * r2;
+            let disc = s * (2.0 * r3 + s);
+
+            let mut u = r;
+            if disc >= 0.0 {
+                // 桁落ちを避けるよう平方根の符号を選ぶ
+                let mut t3 = s + r3;
+                t3 += if t3 < 0.0 { -disc.sqrt() } else { disc.sqrt() };
+                let t = t3.cbrt();
+                u += t + if t != 0.0 { r2 / t } else { 0.0 };
+            } else {
+                // 縮閉線の内側では 3 つの実数解のうち桁落ちしないものを選ぶ
+                let angle = (-disc).sqrt().atan2(-(s + r3));
+                u += 2.0 * r * (angle / 3.0).cos();
+            }
+
+            let v = (u * u + e4 * q).sqrt();
+            let uv = if u < 0.0 { e4 * q / (v - u) } else { u + v };
+            let w = (e2 * (uv - q) / (2.0 * v)).max(0.0);
+            let k = uv / ((uv + w * w).sqrt() + w);
+            let d = k * r_xy / (k + e2);
+            let norm = (z / k).hypot(r_xy / (k + e2));
+            (
+                z / k / norm,
+                r_xy / (k + e2) / norm,
+                (1.0 - e2m / k) * d.hypot(z),
+            )
+        } else {
+            // 赤道面上の地球の中心付近では k = 0 となるため、極限をとる
+            let zz = ((e4 - p) / e2m).sqrt();
+            let xx = p.sqrt();
+            let norm = zz.hypot(xx);
+            let sin_lat = if z < 0.0 { -zz / norm } else { zz / norm };
+            (sin_lat, xx / norm, -a * e2m * norm / e2)
+        };
+
+        [sin_lat.atan2(cos_lat).to_degrees(), lon.to_degrees(), h]
+    }
+}
+
+impl Default for Ellipsoid {
+    fn default() -> Self {
+        Ellipsoid::WGS84
+    }
+}
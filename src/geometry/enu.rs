@@ -0,0 +1,378 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::geometry::{coordinate::Coordinate, ecef::Ecef};
+
+/// 基準点の局所接平面座標系 ENU（東・北・上）で表した位置または変位を表します。
+///
+/// 単位はすべてメートルです。基準点は [`EnuFrame`] が保持します。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Enu {
+    east: f64,
+    north: f64,
+    up: f64,
+}
+
+impl Enu {
+    /// 東・北・上の成分から [`Enu`] を生成します。
+    ///
+    /// # Examples
+    /// ```
+    /// use kasane_logic::geometry::enu::Enu;
+    ///
+    /// let enu = Enu::new(1.0, 2.0, 3.0);
+    ///
+    /// assert_eq!(enu.as_east(), 1.0);
+    /// assert_eq!(enu.as_north(), 2.0);
+    /// assert_eq!(enu.as_up(), 3.0);
+    /// ```
+    pub fn new(east: f64, north: f64, up: f64) -> Enu {
+        Enu { east, north, up }
+    }
+
+    /// 東の成分を返します。
+    pub fn as_east(&self) -> f64 {
+        self.east
+    }
+
+    /// 北の成分を返します。
+    pub fn as_north(&self) -> f64 {
+        self.north
+    }
+
+    /// 上の成分を返します。
+    pub fn as_up(&self) -> f64 {
+        self.up
+    }
+
+    /// 内積を返します。
+    pub fn dot(&self, other: &Enu) -> f64 {
+        self.east * other.east + self.north * other.north + self.up * other.up
+    }
+
+    /// 外積を返します。
+    ///
+    /// # Examples
+    /// ```
+    /// use kasane_logic::geometry::enu::Enu;
+    ///
+    /// let east = Enu::new(1.0, 0.0, 0.0);
+    /// let north = Enu::new(0.0, 1.0, 0.0);
+    ///
+    /// assert_eq!(east.cross(&north), Enu::new(0.0, 0.0, 1.0));
+    /// ```
+    pub fn cross(&self, other: &Enu) -> Enu {
+        Enu::new(
+            self.north * other.up - self.up * other.north,
+            self.up * other.east - self.east * other.up,
+            self.east * other.north - self.north * other.east,
+        )
+    }
+
+    /// 長さを返します。
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+}
+
+impl Add for Enu {
+    type Output = Enu;
+
+    fn add(self, rhs: Enu) -> Enu {
+        Enu::new(
+            self.east + rhs.east,
+            self.north + rhs.north,
+            self.up + rhs.up,
+        )
+    }
+}
+
+impl Sub for Enu {
+    type Output = Enu;
+
+    fn sub(self, rhs: Enu) -> Enu {
+        Enu::new(
+            self.east - rhs.east,
+            self.north - rhs.north,
+            self.up - rhs.up,
+        )
+    }
+}
+
+impl Mul<f64> for Enu {
+    type Output = Enu;
+
+    fn mul(self, rhs: f64) -> Enu {
+        Enu::new(self.east * rhs, self.north * rhs, self.up * rhs)
+    }
+}
+
+impl Neg for Enu {
+    type Output = Enu;
+
+    fn neg(self) -> Enu {
+        Enu::new(-self.east, -self.north, -self.up)
+    }
+}
+
+/// 基準点の局所接平面座標系 NED（北・東・下）で表した位置または変位を表します。
+///
+/// 航空機やセンサーの姿勢を扱う際に用いられる表現で、[`Enu`] と相互に変換できます。
+///
+/// ```
+/// use kasane_logic::geometry::enu::{Enu, Ned};
+///
+/// let ned: Ned = Enu::new(1.0, 2.0, 3.0).into();
+/// assert_eq!((ned.as_north(), ned.as_east(), ned.as_down()), (2.0, 1.0, -3.0));
+/// assert_eq!(Enu::from(ned), Enu::new(1.0, 2.0, 3.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Ned {
+    north: f64,
+    east: f64,
+    down: f64,
+}
+
+impl Ned {
+    /// 北・東・下の成分から [`Ned`] を生成します。
+    pub fn new(north: f64, east: f64, down: f64) -> Ned {
+        Ned { north, east, down }
+    }
+
+    /// 北の成分を返します。
+    pub fn as_north(&self) -> f64 {
+        self.north
+    }
+
+    /// 東の成分を返します。
+    pub fn as_east(&self) -> f64 {
+        self.east
+    }
+
+    /// 下の成分を返します。
+    pub fn as_down(&self) -> f64 {
+        self.down
+    }
+}
+
+impl From<Enu> for Ned {
+    fn from(value: Enu) -> Self {
+        Ned::new(value.north, value.east, -value.up)
+    }
+}
+
+impl From<Ned> for Enu {
+    fn from(value: Ned) -> Self {
+        Enu::new(value.east, value.north, -value.down)
+    }
+}
+
+/// 基準点から見た方位角・仰角・距離で表した位置を表します。
+///
+/// 方位角は北から時計回りに `0.0..360.0` 度、仰角は水平面から上向きを正とする度、距離はメートルです。
+///
+/// ```
+/// use kasane_logic::geometry::enu::{Aer, Enu};
+///
+/// let aer: Aer = Enu::new(100.0, 0.0, 100.0).into();
+/// assert!((aer.as_azimuth() - 90.0).abs() < 1e-9);
+/// assert!((aer.as_elevation() - 45.0).abs() < 1e-9);
+/// assert!((aer.as_range() - 100.0 * 2_f64.sqrt()).abs() < 1e-9);
+///
+/// let enu = Enu::from(aer);
+/// assert!((enu - Enu::new(100.0, 0.0, 100.0)).norm() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Aer {
+    azimuth: f64,
+    elevation: f64,
+    range: f64,
+}
+
+impl Aer {
+    /// 方位角（度）・仰角（度）・距離（m）から [`Aer`] を生成します。
+    pub fn new(azimuth: f64, elevation: f64, range: f64) -> Aer {
+        Aer {
+            azimuth,
+            elevation,
+            range,
+        }
+    }
+
+    /// 方位角（度）を返します。
+    pub fn as_azimuth(&self) -> f64 {
+        self.azimuth
+    }
+
+    /// 仰角（度）を返します。
+    pub fn as_elevation(&self) -> f64 {
+        self.elevation
+    }
+
+    /// 距離（m）を返します。
+    pub fn as_range(&self) -> f64 {
+        self.range
+    }
+}
+
+impl From<Enu> for Aer {
+    fn from(value: Enu) -> Self {
+        let horizontal = value.east.hypot(value.north);
+        Aer::new(
+            value.east.atan2(value.north).to_degrees().rem_euclid(360.0),
+            value.up.atan2(horizontal).to_degrees(),
+            value.norm(),
+        )
+    }
+}
+
+impl From<Aer> for Enu {
+    fn from(value: Aer) -> Self {
+        let (sin_az, cos_az) = value.azimuth.to_radians().sin_cos();
+        let (sin_el, cos_el) = value.elevation.to_radians().sin_cos();
+        let horizontal = value.range * cos_el;
+        Enu::new(
+            horizontal * sin_az,
+            horizontal * cos_az,
+            value.range * sin_el,
+        )
+    }
+}
+
+/// 基準点に固定された局所接平面座標系 ENU
+///
+/// 基準点を原点とし、基準点の楕円体の法線方向を上とする。
+///
+/// ```
+/// use kasane_logic::geometry::coordinate::Coordinate;
+/// use kasane_logic::geometry::ecef::Ecef;
+/// use kasane_logic::geometry::enu::{Enu, EnuFrame};
+///
+/// let frame = EnuFrame::new(Coordinate::new(35.0, 139.0, 0.0).unwrap());
+///
+/// // 北へ約 100m の地点
+/// let point: Ecef = Coordinate::new(35.0009, 139.0, 0.0).unwrap().into();
+/// let enu = frame.to_enu(&point);
+/// assert!(enu.as_east().abs() < 1e-6);
+/// assert!((enu.as_north() - 99.8).abs() < 0.1);
+/// assert!(enu.as_up() < 0.0);
+///
+/// // ENU から ECEF へ戻す
+/// assert!(frame.to_ecef(&enu).distance(&point) < 1e-6);
+///
+/// // 上空 50m の地点
+/// let above = frame.to_ecef(&Enu::new(0.0, 0.0, 50.0));
+/// let coordinate: Coordinate = above.try_into().unwrap();
+/// assert!((coordinate.as_altitude() - 50.0).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EnuFrame {
+    origin: Coordinate,
+    origin_ecef: Ecef,
+    /// 東・北・上の単位ベクトルを ECEF で表したもの
+    basis: [Ecef; 3],
+}
+
+impl EnuFrame {
+    /// 基準点から [`EnuFrame`] を生成します。
+    pub fn new(origin: Coordinate) -> EnuFrame {
+        let (sin_lat, cos_lat) = origin.as_latitude().to_radians().sin_cos();
+        let (sin_lon, cos_lon) = origin.as_longitude().to_radians().sin_cos();
+        EnuFrame {
+            origin,
+            origin_ecef: origin.into(),
+            basis: [
+                Ecef::new(-sin_lon, cos_lon, 0.0),
+                Ecef::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat),
+                Ecef::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat),
+            ],
+        }
+    }
+
+    /// 基準点を返します。
+    pub fn as_origin(&self) -> Coordinate {
+        self.origin
+    }
+
+    /// ECEF 上の点を、この座標系の [`Enu`] へ変換します。
+    pub fn to_enu(&self, point: &Ecef) -> Enu {
+        self.rotate_to_enu(&(*point - self.origin_ecef))
+    }
+
+    /// この座標系の [`Enu`] を、ECEF 上の点へ変換します。
+    pub fn to_ecef(&self, enu: &Enu) -> Ecef {
+        self.origin_ecef + self.rotate_to_ecef(enu)
+    }
+
+    /// ECEF 上の点を、この座標系の [`Ned`] へ変換します。
+    pub fn to_ned(&self, point: &Ecef) -> Ned {
+        self.to_enu(point).into()
+    }
+
+    /// この座標系の [`Ned`] を、ECEF 上の点へ変換します。
+    ///
+    /// ```
+    /// use kasane_logic::geometry::coordinate::Coordinate;
+    /// use kasane_logic::geometry::ecef::Ecef;
+    /// use kasane_logic::geometry::enu::{EnuFrame, Ned};
+    ///
+    /// let frame = EnuFrame::new(Coordinate::new(35.0, 139.0, 0.0).unwrap());
+    ///
+    /// // 北へ 100m、下へ 20m の地点
+    /// let point = frame.from_ned(&Ned::new(100.0, 0.0, 20.0));
+    /// let ned = frame.to_ned(&point);
+    /// assert!((ned.as_north() - 100.0).abs() < 1e-6);
+    /// assert!((ned.as_down() - 20.0).abs() < 1e-6);
+    /// ```
+    pub fn from_ned(&self, ned: &Ned) -> Ecef {
+        self.to_ecef(&(*ned).into())
+    }
+
+    /// ECEF 上の点を、基準点から見た [`Aer`] へ変換します。
+    ///
+    /// # Examples
+    /// ```
+    /// use kasane_logic::geometry::coordinate::Coordinate;
+    /// use kasane_logic::geometry::enu::EnuFrame;
+    ///
+    /// let station = EnuFrame::new(Coordinate::new(35.0, 139.0, 0.0).unwrap());
+    /// let drone = Coordinate::new(35.0, 139.001, 150.0).unwrap();
+    ///
+    /// let aer = station.to_aer(&drone.into());
+    /// assert!((aer.as_azimuth() - 90.0).abs() < 0.1);
+    /// assert!(aer.as_elevation() > 0.0);
+    /// ```
+    pub fn to_aer(&self, point: &Ecef) -> Aer {
+        self.to_enu(point).into()
+    }
+
+    /// 基準点から見た [`Aer`] を、ECEF 上の点へ変換します。
+    ///
+    /// ```
+    /// use kasane_logic::geometry::coordinate::Coordinate;
+    /// use kasane_logic::geometry::enu::{Aer, EnuFrame};
+    ///
+    /// let station = EnuFrame::new(Coordinate::new(35.0, 139.0, 0.0).unwrap());
+    ///
+    /// // 東へ仰角 30° の方向に 1km 離れた地点
+    /// let target = station.from_aer(&Aer::new(90.0, 30.0, 1_000.0));
+    /// let aer = station.to_aer(&target);
+    /// assert!((aer.as_azimuth() - 90.0).abs() < 1e-9);
+    /// assert!((aer.as_elevation() - 30.0).abs() < 1e-9);
+    /// assert!((aer.as_range() - 1_000.0).abs() < 1e-6);
+    /// ```
+    pub fn from_aer(&self, aer: &Aer) -> Ecef {
+        self.to_ecef(&(*aer).into())
+    }
+
+    /// ECEF で表した変位ベクトルを、基準点の ENU の成分へ回転する。
+    pub(crate) fn rotate_to_enu(&self, v: &Ecef) -> Enu {
+        let [east, north, up] = self.basis.map(|axis| v.dot(&axis));
+        Enu::new(east, north, up)
+    }
+
+    /// ENU で表した変位ベクトルを、ECEF の成分へ回転する。
+    pub(crate) fn rotate_to_ecef(&self, v: &Enu) -> Ecef {
+        let [east, north, up] = self.basis;
+        east * v.east + north * v.north + up * v.up
+    }
+}
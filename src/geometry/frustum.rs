@@ -0,0 +1,246 @@
+//! カメラの視錐台による空間 ID の選別
+//!
+//! 3D 表示で描画する空間 ID を、視錐台の外側にあるものを除いたうえで、画面上の大きさに
+//! 応じた粗いズームレベルに置き換えて絞り込むために使用します。
+
+use std::collections::BTreeSet;
+
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        helpers::{cell_bound, cell_vertices, ecef_array, norm, sub},
+        shapes::{
+            Relation, Shape,
+            orientation::{LocalFrame, Orientation},
+        },
+    },
+    spatial_id::single::SingleId,
+};
+
+/// 位置・姿勢・画角・描画範囲で定義されたカメラ
+///
+/// 視線はカメラの姿勢の前方とし、[`Shape`] としては視錐台を表す。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::frustum::Camera;
+/// # use kasane_logic::geometry::shapes::{Shape, orientation::Orientation};
+/// // 高度 100m から北を向く、縦の画角 60° のカメラ
+/// let position = Coordinate::new(35.0, 139.0, 100.0).unwrap();
+/// let camera = Camera::new(position, Orientation::new(0.0, 0.0, 0.0), 60.0, 1.0, 1000.0, [1920, 1080])
+///     .unwrap();
+///
+/// assert!(camera.contains(&Coordinate::new(35.002, 139.0, 100.0).unwrap()));
+/// assert!(!camera.contains(&Coordinate::new(34.998, 139.0, 100.0).unwrap()));
+/// assert!(!camera.contains(&Coordinate::new(35.002, 139.0, 300.0).unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    position: Coordinate,
+    orientation: Orientation,
+    fov: f64,
+    near: f64,
+    far: f64,
+    viewport: [u32; 2],
+    frame: LocalFrame,
+}
+
+impl Camera {
+    /// 位置・姿勢・縦の画角（度）・描画範囲の近端と遠端（m）・画面の幅と高さ（ピクセル）から
+    /// [`Camera`] を生成します。
+    ///
+    /// 横の画角は画面の縦横比から定まります。画面の高さが 0 の場合は 1 ピクセルとして扱います。
+    ///
+    /// # 戻り値
+    /// * 画角が 0° より大きく 180° 未満でない場合や、描画範囲が `0.0 <= near < far` を満たす
+    ///   有限の値でない場合は [`Error::CameraOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::frustum::Camera;
+    /// # use kasane_logic::geometry::shapes::orientation::Orientation;
+    /// let position = Coordinate::new(35.0, 139.0, 100.0).unwrap();
+    /// let orientation = Orientation::new(0.0, 0.0, 0.0);
+    ///
+    /// assert_eq!(
+    ///     Camera::new(position, orientation, 180.0, 1.0, 1000.0, [1920, 1080]).unwrap_err(),
+    ///     Error::CameraOutOfRange { fov: 180.0, near: 1.0, far: 1000.0 }
+    /// );
+    /// assert!(Camera::new(position, orientation, 60.0, -1.0, 1000.0, [1920, 1080]).is_err());
+    /// assert!(Camera::new(position, orientation, 60.0, 1000.0, 1.0, [1920, 1080]).is_err());
+    /// ```
+    pub fn new(
+        position: Coordinate,
+        orientation: Orientation,
+        fov: f64,
+        near: f64,
+        far: f64,
+        viewport: [u32; 2],
+    ) -> Result<Camera, Error> {
+        let valid_fov = fov > 0.0 && fov < 180.0;
+        let valid_range = near >= 0.0 && near < far && far.is_finite();
+        if !(valid_fov && valid_range) {
+            return Err(Error::CameraOutOfRange { fov, near, far });
+        }
+        Ok(Camera {
+            position,
+            orientation,
+            fov,
+            near,
+            far,
+            viewport,
+            frame: LocalFrame::new(position, orientation),
+        })
+    }
+
+    /// 位置を返します。
+    pub fn as_position(&self) -> Coordinate {
+        self.position
+    }
+
+    /// 姿勢を返します。
+    pub fn as_orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// 縦の画角（度）を返します。
+    pub fn as_fov(&self) -> f64 {
+        self.fov
+    }
+
+    /// 描画範囲の近端（m）を返します。
+    pub fn as_near(&self) -> f64 {
+        self.near
+    }
+
+    /// 描画範囲の遠端（m）を返します。
+    pub fn as_far(&self) -> f64 {
+        self.far
+    }
+
+    /// 画面の幅と高さ（ピクセル）を返します。
+    pub fn as_viewport(&self) -> [u32; 2] {
+        self.viewport
+    }
+
+    /// 空間 ID を画面に投影した際の大きさ（ピクセル）の目安を返します。
+    ///
+    /// 空間 ID を内包する球の直径を、カメラから球までの距離で投影した値です。
+    pub fn projected_size(&self, id: &SingleId) -> f64 {
+        let (center, radius) = cell_bound(id);
+        let distance =
+            (norm(sub(center, ecef_array(self.position.into()))) - radius).max(self.near);
+        let focal = self.viewport[1].max(1) as f64 / 2.0 / (self.fov.to_radians() / 2.0).tan();
+        2.0 * radius / distance * focal
+    }
+
+    /// 視錐台の 6 つの面を、物体の座標系（前方・左方・上方）での単位法線と定数項の組で返す。
+    ///
+    /// 点 `p` は、すべての面について `dot(normal, p) + offset ≥ 0` のとき視錐台の内部にある。
+    fn planes(&self) -> [([f64; 3], f64); 6] {
+        let tan_v = (self.fov.to_radians() / 2.0).tan();
+        let tan_h = tan_v * self.viewport[0] as f64 / self.viewport[1].max(1) as f64;
+        let side = |n: [f64; 3]| n.map(|v| v / norm(n));
+        [
+            ([1.0, 0.0, 0.0], -self.near),
+            ([-1.0, 0.0, 0.0], self.far),
+            (side([tan_h, -1.0, 0.0]), 0.0),
+            (side([tan_h, 1.0, 0.0]), 0.0),
+            (side([tan_v, 0.0, -1.0]), 0.0),
+            (side([tan_v, 0.0, 1.0]), 0.0),
+        ]
+    }
+}
+
+impl Shape for Camera {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let (vertices, margin) = cell_vertices(id);
+        let local = vertices.map(|v| self.frame.local(v));
+
+        let mut inside = true;
+        for (normal, offset) in self.planes() {
+            let distances =
+                local.map(|p| normal[0] * p[0] + normal[1] * p[1] + normal[2] * p[2] + offset);
+            // いずれかの面に対してすべての頂点が外側にあれば共有部分を持たない
+            if distances.iter().all(|&d| d < -margin) {
+                return Relation::Outside;
+            }
+            inside &= distances.iter().all(|&d| d >= margin);
+        }
+
+        if inside {
+            Relation::Inside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        let p = self.frame.local(ecef_array((*point).into()));
+        self.planes()
+            .iter()
+            .all(|(n, offset)| n[0] * p[0] + n[1] * p[1] + n[2] * p[2] + offset >= 0.0)
+    }
+}
+
+/// ズームレベルの混在した空間 ID のうち、カメラの視錐台と共有部分を持つものを描画用に選別する。
+///
+/// 各空間 ID は、親の [`Camera::projected_size`] が `lod_pixels` 以下である限り親に置き換えられ、
+/// 画面上で細かすぎる空間 ID はまとめて粗いズームレベルで描画される。
+/// 戻り値は昇順に整列され、祖先が含まれる空間 ID は除かれる。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::frustum::{cull, Camera};
+/// # use kasane_logic::geometry::shapes::orientation::Orientation;
+/// let position = Coordinate::new(35.0, 139.0, 100.0).unwrap();
+/// let camera = Camera::new(position, Orientation::new(0.0, 0.0, 0.0), 60.0, 1.0, 1000.0, [1920, 1080])
+///     .unwrap();
+///
+/// let ahead = Coordinate::new(35.002, 139.0, 100.0).unwrap().to_single_id(22);
+/// let behind = Coordinate::new(34.998, 139.0, 100.0).unwrap().to_single_id(22);
+/// let beyond = Coordinate::new(35.02, 139.0, 100.0).unwrap().to_single_id(22);
+/// // 約 800m 先にある 1m 程度の細かい空間 ID
+/// let distant = Coordinate::new(35.0072, 139.0, 100.0).unwrap().to_single_id(25);
+///
+/// let ids = [ahead.clone(), behind, beyond, distant.clone()];
+/// let drawn = cull(&camera, &ids, 8.0);
+///
+/// assert_eq!(drawn.len(), 2);
+/// assert!(drawn.contains(&ahead));
+/// let lod = drawn.iter().find(|id| **id != ahead).unwrap();
+/// assert!(lod.as_z() < 25);
+/// assert_eq!(distant.parent(25 - lod.as_z()).as_ref(), Some(lod));
+/// ```
+pub fn cull<'a>(
+    camera: &Camera,
+    ids: impl IntoIterator<Item = &'a SingleId>,
+    lod_pixels: f64,
+) -> Vec<SingleId> {
+    let mut drawn: BTreeSet<SingleId> = BTreeSet::new();
+    for id in ids {
+        if camera.relation(id) == Relation::Outside {
+            continue;
+        }
+        let mut lod = id.clone();
+        while let Some(parent) = lod.parent(1)
+            && camera.projected_size(&parent) <= lod_pixels
+        {
+            lod = parent;
+        }
+        drawn.insert(lod);
+    }
+
+    drawn
+        .iter()
+        .filter(|id| {
+            (1..=id.as_z()).all(|difference| {
+                id.parent(difference)
+                    .is_none_or(|ancestor| !drawn.contains(&ancestor))
+            })
+        })
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,884 @@
+//! 楕円体上の測地線の計算
+//!
+//! Karney (2013) "Algorithms for geodesics" の方法により、2 点間の測地線長と両端の方位角を
+//! 求める逆問題と、始点・方位角・距離から終点を求める順問題を解きます。級数は扁平率の
+//! 6 次まで展開しており、WGS-84 楕円体では対蹠点付近を含めて 15nm 程度の精度で求まります。
+//!
+//! 緯度・経度・方位角は度、距離はメートルで扱い、方位角は北から時計回りに測ります。
+
+use std::f64::consts::PI;
+
+use crate::geometry::ellipsoid::Ellipsoid;
+
+/// 級数展開の次数
+const ORDER: usize = 6;
+
+/// 機械イプシロン
+const TOL0: f64 = f64::EPSILON;
+const TOL1: f64 = 200.0 * TOL0;
+/// 機械イプシロンの平方根
+const TOL2: f64 = 1.490_116_119_384_765_6e-8;
+const TOLB: f64 = TOL0 * TOL2;
+const XTHRESH: f64 = 1000.0 * TOL2;
+/// 正規化数の最小値の平方根
+const TINY: f64 = 1.491_668_146_240_041_3e-154;
+
+/// Newton 法の反復回数の上限。以降は二分法に切り替える。
+const MAXIT1: usize = 20;
+const MAXIT2: usize = MAXIT1 + f64::MANTISSA_DIGITS as usize + 10;
+
+/// `A3` の係数（`n` の多項式）
+const A3_COEFF: [f64; 18] = [
+    -3.0, 128.0, //
+    -2.0, -3.0, 64.0, //
+    -1.0, -3.0, -1.0, 16.0, //
+    3.0, -1.0, -2.0, 8.0, //
+    1.0, -1.0, 2.0, //
+    1.0, 1.0,
+];
+
+/// `C3` の係数（`n` の多項式）
+const C3_COEFF: [f64; 45] = [
+    3.0, 128.0, //
+    2.0, 5.0, 128.0, //
+    -1.0, 3.0, 3.0, 64.0, //
+    -1.0, 0.0, 1.0, 8.0, //
+    -1.0, 1.0, 4.0, //
+    5.0, 256.0, //
+    1.0, 3.0, 128.0, //
+    -3.0, -2.0, 3.0, 64.0, //
+    1.0, -3.0, 2.0, 32.0, //
+    7.0, 512.0, //
+    -10.0, 9.0, 384.0, //
+    5.0, -9.0, 5.0, 192.0, //
+    7.0, 512.0, //
+    -14.0, 7.0, 512.0, //
+    21.0, 2560.0,
+];
+
+/// `C1` の係数（`ε²` の多項式）
+const C1_COEFF: [f64; 18] = [
+    -1.0, 6.0, -16.0, 32.0, //
+    -9.0, 64.0, -128.0, 2048.0, //
+    9.0, -16.0, 768.0, //
+    3.0, -5.0, 512.0, //
+    -7.0, 1280.0, //
+    -7.0, 2048.0,
+];
+
+/// `C1'` の係数（`ε²` の多項式）
+const C1P_COEFF: [f64; 18] = [
+    205.0, -432.0, 768.0, 1536.0, //
+    4005.0, -4736.0, 3840.0, 12288.0, //
+    -225.0, 116.0, 384.0, //
+    -7173.0, 2695.0, 7680.0, //
+    3467.0, 7680.0, //
+    38081.0, 61440.0,
+];
+
+/// `C2` の係数（`ε²` の多項式）
+const C2_COEFF: [f64; 18] = [
+    1.0, 2.0, 16.0, 32.0, //
+    35.0, 64.0, 384.0, 2048.0, //
+    15.0, 80.0, 768.0, //
+    7.0, 35.0, 512.0, //
+    63.0, 1280.0, //
+    77.0, 2048.0,
+];
+
+/// 楕円体上の測地線の計算器
+///
+/// 楕円体の長半径と扁平率から級数の係数をあらかじめ求めておき、逆問題と順問題を解きます。
+/// 範囲を検証した楕円体から求めるため、[`Geodesic::wgs84`] または [`Ellipsoid::geodesic`] で生成します。
+///
+/// ```
+/// # use kasane_logic::geometry::geodesic::Geodesic;
+/// let geodesic = Geodesic::wgs84();
+///
+/// // ニューヨーク（JFK）からロンドン（LHR）まで
+/// let inverse = geodesic.inverse(40.6, -73.8, 51.6, -0.5);
+/// assert!((inverse.as_distance() - 5_551_759.400).abs() < 1e-3);
+/// assert!((inverse.as_azimuth1() - 51.198_882_85).abs() < 1e-8);
+/// assert!((inverse.as_azimuth2() - 107.821_776_74).abs() < 1e-8);
+///
+/// // 逆問題で得た方位角と距離から終点を求め直す
+/// let direct = geodesic.direct(40.6, -73.8, inverse.as_azimuth1(), inverse.as_distance());
+/// assert!((direct.as_latitude() - 51.6).abs() < 1e-12);
+/// assert!((direct.as_longitude() + 0.5).abs() < 1e-12);
+/// assert!((direct.as_azimuth() - inverse.as_azimuth2()).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Geodesic {
+    a: f64,
+    f: f64,
+    f1: f64,
+    ep2: f64,
+    n: f64,
+    b: f64,
+    etol2: f64,
+    a3x: [f64; ORDER],
+    c3x: [f64; ORDER * (ORDER - 1) / 2],
+}
+
+/// 測地線の逆問題の解
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeodesicInverse {
+    distance: f64,
+    azimuth1: f64,
+    azimuth2: f64,
+}
+
+impl GeodesicInverse {
+    /// 測地線長（m）を返します。
+    pub fn as_distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// 始点における方位角（度）を返します。
+    pub fn as_azimuth1(&self) -> f64 {
+        self.azimuth1
+    }
+
+    /// 終点における方位角（度）を返します。
+    ///
+    /// 終点から先へ進む向きの方位角であり、終点から始点へ戻る向きではありません。
+    pub fn as_azimuth2(&self) -> f64 {
+        self.azimuth2
+    }
+}
+
+/// 測地線の順問題の解
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeodesicDirect {
+    latitude: f64,
+    longitude: f64,
+    azimuth: f64,
+}
+
+impl GeodesicDirect {
+    /// 終点の緯度（度）を返します。
+    pub fn as_latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    /// 終点の経度（度）を返します。`-180.0..=180.0` の範囲に正規化されます。
+    pub fn as_longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// 終点における方位角（度）を返します。
+    pub fn as_azimuth(&self) -> f64 {
+        self.azimuth
+    }
+}
+
+/// 逆問題の反復で得られる、始点の方位角に対する経度差とその途中の値
+struct Lambda {
+    lam12: f64,
+    salp2: f64,
+    calp2: f64,
+    sig12: f64,
+    ssig1: f64,
+    csig1: f64,
+    ssig2: f64,
+    csig2: f64,
+    eps: f64,
+    dlam12: f64,
+}
+
+impl Geodesic {
+    /// 範囲の検証を行わずに、長半径 `a`（m）と扁平率 `f` の楕円体から生成する。
+    ///
+    /// 級数は扁球を前提とするため、`a > 0`・`0 <= f < 1` を満たす値のみを与える。
+    pub(crate) fn new(a: f64, f: f64) -> Geodesic {
+        let f1 = 1.0 - f;
+        let e2 = f * (2.0 - f);
+        let n = f / (2.0 - f);
+
+        let mut a3x = [0.0; ORDER];
+        let mut o = 0;
+        for (k, j) in (0..ORDER).rev().enumerate() {
+            let m = (ORDER - j - 1).min(j);
+            a3x[k] = polyval(&A3_COEFF[o..=o + m], n) / A3_COEFF[o + m + 1];
+            o += m + 2;
+        }
+
+        let mut c3x = [0.0; ORDER * (ORDER - 1) / 2];
+        let (mut o, mut k) = (0, 0);
+        for l in 1..ORDER {
+            for j in (l..ORDER).rev() {
+                let m = (ORDER - j - 1).min(j);
+                c3x[k] = polyval(&C3_COEFF[o..=o + m], n) / C3_COEFF[o + m + 1];
+                k += 1;
+                o += m + 2;
+            }
+        }
+
+        Geodesic {
+            a,
+            f,
+            f1,
+            ep2: e2 / (f1 * f1),
+            n,
+            b: a * f1,
+            etol2: 0.1 * TOL2 / ((f.abs().max(0.001) * (1.0 - f / 2.0).min(1.0)) / 2.0).sqrt(),
+            a3x,
+            c3x,
+        }
+    }
+
+    /// WGS-84 楕円体の [`Geodesic`] を生成します。
+    ///
+    /// 他の楕円体の場合は [`Ellipsoid::geodesic`] を使用します。
+    pub fn wgs84() -> Geodesic {
+        Ellipsoid::WGS84.geodesic()
+    }
+
+    /// 長半径（m）を返します。
+    pub fn as_a(&self) -> f64 {
+        self.a
+    }
+
+    /// 扁平率を返します。
+    pub fn as_f(&self) -> f64 {
+        self.f
+    }
+
+    /// 2 点間の測地線長と、両端における方位角を求めます（逆問題）。
+    ///
+    /// 対蹠点を結ぶ場合など測地線が一意に定まらない場合は、そのうちの 1 本の方位角を返します。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::geodesic::Geodesic;
+    /// let geodesic = Geodesic::wgs84();
+    ///
+    /// // 赤道上の 1° は長半径 × π / 180
+    /// let equator = geodesic.inverse(0.0, 0.0, 0.0, 1.0);
+    /// assert!((equator.as_distance() - 111_319.491).abs() < 1e-3);
+    /// assert_eq!(equator.as_azimuth1(), 90.0);
+    ///
+    /// // 経線に沿って北極から南極まで
+    /// let meridian = geodesic.inverse(90.0, 0.0, -90.0, 0.0);
+    /// assert!((meridian.as_distance() - 20_003_931.459).abs() < 1e-3);
+    /// ```
+    pub fn inverse(&self, lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> GeodesicInverse {
+        // 経度差を 0°..=180° に、始点を南半球の高緯度側にそろえて解き、最後に符号を戻す
+        let (lon12, lon12s) = ang_diff(lon1, lon2);
+        let mut lonsign = 1_f64.copysign(lon12);
+        let lon12 = lonsign * ang_round(lon12);
+        let lon12s = ang_round((180.0 - lon12) - lonsign * lon12s);
+        let lam12 = lon12.to_radians();
+        let (slam12, clam12) = if lon12 > 90.0 {
+            let (s, c) = sincosd(lon12s);
+            (s, -c)
+        } else {
+            sincosd(lon12)
+        };
+
+        let mut lat1 = ang_round(lat_fix(lat1));
+        let mut lat2 = ang_round(lat_fix(lat2));
+        let swapp = if lat1.abs() < lat2.abs() || lat2.is_nan() {
+            -1.0
+        } else {
+            1.0
+        };
+        if swapp < 0.0 {
+            lonsign = -lonsign;
+            std::mem::swap(&mut lat1, &mut lat2);
+        }
+        let latsign = 1_f64.copysign(-lat1);
+        lat1 *= latsign;
+        lat2 *= latsign;
+
+        let (sbet1, cbet1) = self.reduced_latitude(lat1);
+        let (mut sbet2, mut cbet2) = self.reduced_latitude(lat2);
+        if cbet1 < -sbet1 {
+            if cbet2 == cbet1 {
+                sbet2 = sbet1.copysign(sbet2);
+            }
+        } else if sbet2.abs() == -sbet1 {
+            cbet2 = cbet1;
+        }
+        let dn1 = (1.0 + self.ep2 * sbet1 * sbet1).sqrt();
+        let dn2 = (1.0 + self.ep2 * sbet2 * sbet2).sqrt();
+
+        let (mut salp1, mut calp1, mut salp2, mut calp2) = (0.0, 0.0, 0.0, 0.0);
+        let mut s12x = f64::NAN;
+
+        let mut meridian = lat1 == -90.0 || slam12 == 0.0;
+        if meridian {
+            // 経線に沿う場合
+            (salp1, calp1, salp2, calp2) = (slam12, clam12, 0.0, 1.0);
+            let (ssig1, csig1) = (sbet1, calp1 * cbet1);
+            let (ssig2, csig2) = (sbet2, calp2 * cbet2);
+            let sig12 = (csig1 * ssig2 - ssig1 * csig2)
+                .max(0.0)
+                .atan2(csig1 * csig2 + ssig1 * ssig2);
+            let (s12, m12) = self.lengths(self.n, sig12, [ssig1, csig1, dn1], [ssig2, csig2, dn2]);
+            // 共役点を越える場合は経線が最短とならない
+            if sig12 < 1.0 || m12 >= 0.0 {
+                let zero = sig12 < 3.0 * TINY || (sig12 < TOL0 && (s12 < 0.0 || m12 < 0.0));
+                s12x = if zero { 0.0 } else { s12 * self.b };
+            } else {
+                meridian = false;
+            }
+        }
+
+        if !meridian && sbet1 == 0.0 && (self.f <= 0.0 || lon12s >= self.f * 180.0) {
+            // 赤道に沿う場合
+            (salp1, calp1, salp2, calp2) = (1.0, 0.0, 1.0, 0.0);
+            s12x = self.a * lam12;
+        } else if !meridian {
+            let (sig12, start, dnm) =
+                self.inverse_start([sbet1, cbet1, sbet2, cbet2], lam12, slam12, clam12);
+            [salp1, calp1] = [start[0], start[1]];
+
+            if sig12 >= 0.0 {
+                // 短い測地線は、平均の緯度の球面上の大円で近似できる
+                [salp2, calp2] = [start[2], start[3]];
+                s12x = sig12 * self.b * dnm;
+            } else {
+                // 始点の方位角を、経度差が一致するまで Newton 法と二分法で求める
+                let mut numit = 0;
+                let (mut tripn, mut tripb) = (false, false);
+                let (mut salp1a, mut calp1a) = (TINY, 1.0);
+                let (mut salp1b, mut calp1b) = (TINY, -1.0);
+                let solution = loop {
+                    let lambda = self.lambda12(
+                        [sbet1, cbet1, dn1],
+                        [sbet2, cbet2, dn2],
+                        salp1,
+                        calp1,
+                        slam12,
+                        clam12,
+                        numit < MAXIT1,
+                    );
+                    let v = lambda.lam12;
+                    let tolerance = if tripn { 8.0 } else { 1.0 } * TOL0;
+                    if tripb || v.is_nan() || v.abs() < tolerance || numit >= MAXIT2 {
+                        break lambda;
+                    }
+
+                    if v > 0.0 && (numit < MAXIT1 || calp1 / salp1 > calp1b / salp1b) {
+                        (salp1b, calp1b) = (salp1, calp1);
+                    } else if v < 0.0 && (numit < MAXIT1 || calp1 / salp1 < calp1a / salp1a) {
+                        (salp1a, calp1a) = (salp1, calp1);
+                    }
+                    numit += 1;
+
+                    if numit < MAXIT1 && lambda.dlam12 > 0.0 {
+                        let dalp1 = -v / lambda.dlam12;
+                        if dalp1.abs() < PI {
+                            let (sdalp1, cdalp1) = dalp1.sin_cos();
+                            let nsalp1 = salp1 * cdalp1 + calp1 * sdalp1;
+                            if nsalp1 > 0.0 {
+                                (salp1, calp1) = norm(nsalp1, calp1 * cdalp1 - salp1 * sdalp1);
+                                tripn = v.abs() <= 16.0 * TOL0;
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Newton 法が範囲外へ出る場合は、既知の上下界で二分する
+                    (salp1, calp1) = norm((salp1a + salp1b) / 2.0, (calp1a + calp1b) / 2.0);
+                    tripn = false;
+                    tripb = (salp1a - salp1).abs() + (calp1a - calp1) < TOLB
+                        || (salp1 - salp1b).abs() + (calp1 - calp1b) < TOLB;
+                };
+
+                (salp2, calp2) = (solution.salp2, solution.calp2);
+                let (s12, _) = self.lengths(
+                    solution.eps,
+                    solution.sig12,
+                    [solution.ssig1, solution.csig1, dn1],
+                    [solution.ssig2, solution.csig2, dn2],
+                );
+                s12x = s12 * self.b;
+            }
+        }
+
+        if swapp < 0.0 {
+            std::mem::swap(&mut salp1, &mut salp2);
+            std::mem::swap(&mut calp1, &mut calp2);
+        }
+        salp1 *= swapp * lonsign;
+        calp1 *= swapp * latsign;
+        salp2 *= swapp * lonsign;
+        calp2 *= swapp * latsign;
+
+        GeodesicInverse {
+            distance: 0.0 + s12x,
+            azimuth1: atan2d(salp1, calp1),
+            azimuth2: atan2d(salp2, calp2),
+        }
+    }
+
+    /// 始点から方位角 `azi1`（度）の向きへ測地線長 `s12`（m）だけ進んだ終点を求めます（順問題）。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::geodesic::Geodesic;
+    /// let geodesic = Geodesic::wgs84();
+    ///
+    /// // 東京駅から真東へ 10km
+    /// let direct = geodesic.direct(35.681382, 139.766084, 90.0, 10_000.0);
+    /// assert!(direct.as_latitude() < 35.681382);
+    /// assert!((direct.as_longitude() - 139.876).abs() < 1e-3);
+    ///
+    /// let inverse = geodesic.inverse(35.681382, 139.766084, direct.as_latitude(), direct.as_longitude());
+    /// assert!((inverse.as_distance() - 10_000.0).abs() < 1e-6);
+    /// assert!((inverse.as_azimuth1() - 90.0).abs() < 1e-9);
+    /// ```
+    pub fn direct(&self, lat1: f64, lon1: f64, azi1: f64, s12: f64) -> GeodesicDirect {
+        let (salp1, calp1) = sincosd(ang_round(azi1));
+        let (sbet1, cbet1) = self.reduced_latitude(ang_round(lat_fix(lat1)));
+
+        // 赤道との交点を基準とした補助球上の弧長 σ と経度 ω
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+        let somg1 = salp0 * sbet1;
+        let comg1 = if sbet1 != 0.0 || calp1 != 0.0 {
+            cbet1 * calp1
+        } else {
+            1.0
+        };
+        let (ssig1, csig1) = norm(sbet1, comg1);
+
+        let k2 = calp0 * calp0 * self.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+        let a1m1 = a1m1f(eps);
+        let c1a = series(&C1_COEFF, eps);
+        let c1pa = series(&C1P_COEFF, eps);
+        let b11 = sin_series(ssig1, csig1, &c1a);
+        let (s, c) = b11.sin_cos();
+        let stau1 = ssig1 * c + csig1 * s;
+        let ctau1 = csig1 * c - ssig1 * s;
+        let a3c = -self.f * salp0 * self.a3f(eps);
+        let c3a = self.c3f(eps);
+        let b31 = sin_series(ssig1, csig1, &c3a);
+
+        // 距離 s12 を補助球上の弧長 σ12 へ変換する
+        let tau12 = s12 / (self.b * (1.0 + a1m1));
+        let (s, c) = tau12.sin_cos();
+        let b12 = -sin_series(stau1 * c + ctau1 * s, ctau1 * c - stau1 * s, &c1pa);
+        let mut sig12 = tau12 - (b12 - b11);
+        let (mut ssig12, mut csig12) = sig12.sin_cos();
+        if self.f.abs() > 0.01 {
+            // 扁平率が大きい場合は Newton 法で 1 回補正する
+            let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+            let csig2 = csig1 * csig12 - ssig1 * ssig12;
+            let b12 = sin_series(ssig2, csig2, &c1a);
+            let serr = (1.0 + a1m1) * (sig12 + (b12 - b11)) - s12 / self.b;
+            sig12 -= serr / (1.0 + k2 * ssig2 * ssig2).sqrt();
+            (ssig12, csig12) = sig12.sin_cos();
+        }
+
+        let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+        let mut csig2 = csig1 * csig12 - ssig1 * ssig12;
+        let sbet2 = calp0 * ssig2;
+        let mut cbet2 = salp0.hypot(calp0 * csig2);
+        if cbet2 == 0.0 {
+            // 極に到達する場合
+            cbet2 = TINY;
+            csig2 = TINY;
+        }
+        let (salp2, calp2) = (salp0, calp0 * csig2);
+
+        let somg2 = salp0 * ssig2;
+        let comg2 = csig2;
+        let omg12 = (somg2 * comg1 - comg2 * somg1).atan2(comg2 * comg1 + somg2 * somg1);
+        let lam12 = omg12 + a3c * (sig12 + (sin_series(ssig2, csig2, &c3a) - b31));
+
+        GeodesicDirect {
+            latitude: atan2d(sbet2, self.f1 * cbet2),
+            longitude: ang_normalize(ang_normalize(lon1) + ang_normalize(lam12.to_degrees())),
+            azimuth: atan2d(salp2, calp2),
+        }
+    }
+
+    /// 緯度（度）に対する更成緯度 β の正弦と余弦を返す。
+    fn reduced_latitude(&self, lat: f64) -> (f64, f64) {
+        let (s, c) = sincosd(lat);
+        let (s, c) = norm(self.f1 * s, c);
+        (s, c.max(TINY))
+    }
+
+    fn a3f(&self, eps: f64) -> f64 {
+        polyval(&self.a3x, eps)
+    }
+
+    /// `C3` の係数を返す。先頭の要素は使用しない。
+    fn c3f(&self, eps: f64) -> [f64; ORDER] {
+        let mut c = [0.0; ORDER];
+        let (mut mult, mut o) = (1.0, 0);
+        for (l, coefficient) in c.iter_mut().enumerate().skip(1) {
+            let m = ORDER - l - 1;
+            mult *= eps;
+            *coefficient = mult * polyval(&self.c3x[o..=o + m], eps);
+            o += m + 1;
+        }
+        c
+    }
+
+    /// 補助球上の弧長 `sig12` に対する、短半径で割った測地線長と換算長を返す。
+    ///
+    /// 両端は `[sin σ, cos σ, √(1 + k² sin² σ)]` で与える。
+    fn lengths(&self, eps: f64, sig12: f64, p1: [f64; 3], p2: [f64; 3]) -> (f64, f64) {
+        let [ssig1, csig1, dn1] = p1;
+        let [ssig2, csig2, dn2] = p2;
+        let a1 = a1m1f(eps);
+        let c1a = series(&C1_COEFF, eps);
+        let a2 = a2m1f(eps);
+        let c2a = series(&C2_COEFF, eps);
+        let m0x = a1 - a2;
+        let (a1, a2) = (1.0 + a1, 1.0 + a2);
+
+        let b1 = sin_series(ssig2, csig2, &c1a) - sin_series(ssig1, csig1, &c1a);
+        let b2 = sin_series(ssig2, csig2, &c2a) - sin_series(ssig1, csig1, &c2a);
+        let j12 = m0x * sig12 + (a1 * b1 - a2 * b2);
+        let s12b = a1 * (sig12 + b1);
+        let m12b = dn2 * (csig1 * ssig2) - dn1 * (ssig1 * csig2) - csig1 * csig2 * j12;
+        (s12b, m12b)
+    }
+
+    /// 逆問題の反復の初期値として、始点の方位角を返す。
+    ///
+    /// 短い測地線の場合は球面近似で解が定まり、非負の `sig12` と終点の方位角、
+    /// 平均の緯度における `√(1 + e'² sin² β)` を合わせて返す。
+    fn inverse_start(
+        &self,
+        [sbet1, cbet1, sbet2, cbet2]: [f64; 4],
+        lam12: f64,
+        slam12: f64,
+        clam12: f64,
+    ) -> (f64, [f64; 4], f64) {
+        let mut sig12 = -1.0;
+        let (mut salp2, mut calp2, mut dnm) = (f64::NAN, f64::NAN, f64::NAN);
+
+        let sbet12 = sbet2 * cbet1 - cbet2 * sbet1;
+        let cbet12 = cbet2 * cbet1 + sbet2 * sbet1;
+        let sbet12a = sbet2 * cbet1 + cbet2 * sbet1;
+        let shortline = cbet12 >= 0.0 && sbet12 < 0.5 && cbet2 * lam12 < 0.5;
+        let (mut somg12, mut comg12) = if shortline {
+            let mut sbetm2 = (sbet1 + sbet2).powi(2);
+            sbetm2 /= sbetm2 + (cbet1 + cbet2).powi(2);
+            dnm = (1.0 + self.ep2 * sbetm2).sqrt();
+            (lam12 / (self.f1 * dnm)).sin_cos()
+        } else {
+            (slam12, clam12)
+        };
+
+        let mut salp1 = cbet2 * somg12;
+        let mut calp1 = if comg12 >= 0.0 {
+            sbet12 + cbet2 * sbet1 * somg12 * somg12 / (1.0 + comg12)
+        } else {
+            sbet12a - cbet2 * sbet1 * somg12 * somg12 / (1.0 - comg12)
+        };
+        let ssig12 = salp1.hypot(calp1);
+        let csig12 = sbet1 * sbet2 + cbet1 * cbet2 * comg12;
+
+        if shortline && ssig12 < self.etol2 {
+            salp2 = cbet1 * somg12;
+            calp2 = sbet12
+                - cbet1
+                    * sbet2
+                    * if comg12 >= 0.0 {
+                        somg12 * somg12 / (1.0 + comg12)
+                    } else {
+                        1.0 - comg12
+                    };
+            (salp2, calp2) = norm(salp2, calp2);
+            sig12 = ssig12.atan2(csig12);
+        } else if self.n.abs() >= 0.1
+            || csig12 >= 0.0
+            || ssig12 >= 6.0 * self.n.abs() * PI * cbet1 * cbet1
+        {
+            // 球面近似の値をそのまま初期値とする
+        } else {
+            // 対蹠点付近では、アステロイド曲線上の近似解を初期値とする
+            let lam12x = (-slam12).atan2(-clam12);
+            let k2 = sbet1 * sbet1 * self.ep2;
+            let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+            let lamscale = self.f * cbet1 * self.a3f(eps) * PI;
+            let betscale = lamscale * cbet1;
+            let x = lam12x / lamscale;
+            let y = sbet12a / betscale;
+
+            if y > -TOL1 && x > -1.0 - XTHRESH {
+                salp1 = (-x).min(1.0);
+                calp1 = -(1.0 - salp1 * salp1).sqrt();
+            } else {
+                let k = astroid(x, y);
+                let omg12a = lamscale * (-x * k / (1.0 + k));
+                (somg12, comg12) = omg12a.sin_cos();
+                comg12 = -comg12;
+                salp1 = cbet2 * somg12;
+                calp1 = sbet12a - cbet2 * sbet1 * somg12 * somg12 / (1.0 - comg12);
+            }
+        }
+
+        let (salp1, calp1) = if salp1 > 0.0 {
+            norm(salp1, calp1)
+        } else {
+            (1.0, 0.0)
+        };
+        (sig12, [salp1, calp1, salp2, calp2], dnm)
+    }
+
+    /// 始点の方位角に対する、終点までの経度差と目標の経度差との差を返す。
+    ///
+    /// `diffp` が `true` の場合は、方位角に関する微分も求める。
+    #[allow(clippy::too_many_arguments)]
+    fn lambda12(
+        &self,
+        [sbet1, cbet1, dn1]: [f64; 3],
+        [sbet2, cbet2, dn2]: [f64; 3],
+        salp1: f64,
+        calp1: f64,
+        slam120: f64,
+        clam120: f64,
+        diffp: bool,
+    ) -> Lambda {
+        let calp1 = if sbet1 == 0.0 && calp1 == 0.0 {
+            -TINY
+        } else {
+            calp1
+        };
+
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+
+        let somg1 = salp0 * sbet1;
+        let comg1 = calp1 * cbet1;
+        let (ssig1, csig1) = norm(sbet1, comg1);
+
+        let salp2 = if cbet2 != cbet1 { salp0 / cbet2 } else { salp1 };
+        let calp2 = if cbet2 != cbet1 || sbet2.abs() != -sbet1 {
+            let d = if cbet1 < -sbet1 {
+                (cbet2 - cbet1) * (cbet1 + cbet2)
+            } else {
+                (sbet1 - sbet2) * (sbet1 + sbet2)
+            };
+            ((calp1 * cbet1).powi(2) + d).sqrt() / cbet2
+        } else {
+            calp1.abs()
+        };
+
+        let somg2 = salp0 * sbet2;
+        let comg2 = calp2 * cbet2;
+        let (ssig2, csig2) = norm(sbet2, comg2);
+
+        let sig12 = (csig1 * ssig2 - ssig1 * csig2)
+            .max(0.0)
+            .atan2(csig1 * csig2 + ssig1 * ssig2);
+        let somg12 = (comg1 * somg2 - somg1 * comg2).max(0.0);
+        let comg12 = comg1 * comg2 + somg1 * somg2;
+        let eta = (somg12 * clam120 - comg12 * slam120).atan2(comg12 * clam120 + somg12 * slam120);
+
+        let k2 = calp0 * calp0 * self.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+        let c3a = self.c3f(eps);
+        let b312 = sin_series(ssig2, csig2, &c3a) - sin_series(ssig1, csig1, &c3a);
+        let domg12 = -self.f * self.a3f(eps) * salp0 * (sig12 + b312);
+
+        let dlam12 = if !diffp {
+            f64::NAN
+        } else if calp2 == 0.0 {
+            -2.0 * self.f1 * dn1 / sbet1
+        } else {
+            let (_, m12b) = self.lengths(eps, sig12, [ssig1, csig1, dn1], [ssig2, csig2, dn2]);
+            m12b * self.f1 / (calp2 * cbet2)
+        };
+
+        Lambda {
+            lam12: eta + domg12,
+            salp2,
+            calp2,
+            sig12,
+            ssig1,
+            csig1,
+            ssig2,
+            csig2,
+            eps,
+            dlam12,
+        }
+    }
+}
+
+/// 係数を次数の高い順に並べた多項式を Horner 法で評価する。
+fn polyval(coeff: &[f64], x: f64) -> f64 {
+    coeff.iter().fold(0.0, |y, &c| y * x + c)
+}
+
+/// `ε²` の多項式として表された係数から、`ε` の級数の係数を求める。先頭の要素は使用しない。
+fn series(coeff: &[f64], eps: f64) -> [f64; ORDER + 1] {
+    let eps2 = eps * eps;
+    let mut c = [0.0; ORDER + 1];
+    let (mut d, mut o) = (eps, 0);
+    for (l, coefficient) in c.iter_mut().enumerate().skip(1) {
+        let m = (ORDER - l) / 2;
+        *coefficient = d * polyval(&coeff[o..=o + m], eps2) / coeff[o + m + 1];
+        o += m + 2;
+        d *= eps;
+    }
+    c
+}
+
+fn a1m1f(eps: f64) -> f64 {
+    let t = polyval(&[1.0, 4.0, 64.0, 0.0], eps * eps) / 256.0;
+    (t + eps) / (1.0 - eps)
+}
+
+fn a2m1f(eps: f64) -> f64 {
+    let t = polyval(&[-11.0, -28.0, -192.0, 0.0], eps * eps) / 256.0;
+    (t - eps) / (1.0 + eps)
+}
+
+/// `Σ c[l] sin(2lσ)` を Clenshaw の方法で評価する。
+fn sin_series(sinx: f64, cosx: f64, c: &[f64]) -> f64 {
+    let ar = 2.0 * (cosx - sinx) * (cosx + sinx);
+    let mut k = c.len();
+    let mut n = k - 1;
+    let (mut y0, mut y1) = (0.0, 0.0);
+    if n % 2 == 1 {
+        k -= 1;
+        y0 = c[k];
+    }
+    n /= 2;
+    while n > 0 {
+        n -= 1;
+        k -= 1;
+        y1 = ar * y0 - y1 + c[k];
+        k -= 1;
+        y0 = ar * y1 - y0 + c[k];
+    }
+    2.0 * sinx * cosx * y0
+}
+
+/// `k⁴ + 2k³ - (x² + y² - 1)k² - 2y²k - y² = 0` の正の根を返す。
+fn astroid(x: f64, y: f64) -> f64 {
+    let p = x * x;
+    let q = y * y;
+    let r = (p + q - 1.0) / 6.0;
+    if q == 0.0 && r <= 0.0 {
+        return 0.0;
+    }
+
+    let s = p * q / 4.0;
+    let r2 = r * r;
+    let r3 = r * r2;
+    let disc = s * (s + 2.0 * r3);
+    let mut u = r;
+    if disc >= 0.0 {
+        let mut t3 = s + r3;
+        t3 += if t3 < 0.0 { -disc.sqrt() } else { disc.sqrt() };
+        let t = t3.cbrt();
+        u += t + if t != 0.0 { r2 / t } else { 0.0 };
+    } else {
+        let ang = (-disc).sqrt().atan2(-(s + r3));
+        u += 2.0 * r * (ang / 3.0).cos();
+    }
+    let v = (u * u + q).sqrt();
+    let uv = if u < 0.0 { q / (v - u) } else { u + v };
+    let w = (uv - q) / (2.0 * v);
+    uv / ((uv + w * w).sqrt() + w)
+}
+
+fn norm(x: f64, y: f64) -> (f64, f64) {
+    let r = x.hypot(y);
+    (x / r, y / r)
+}
+
+/// 丸め誤差を含まない和 `u + v = s + t` を返す。
+fn sum(u: f64, v: f64) -> (f64, f64) {
+    let s = u + v;
+    let up = s - v;
+    let vpp = s - up;
+    (s, -((up - u) + (vpp - v)))
+}
+
+/// `x / y` の剰余を `-y/2..y/2` の範囲で返す。
+fn remainder(x: f64, y: f64) -> f64 {
+    let z = if x == 0.0 { x } else { x % y };
+    if z < -y / 2.0 {
+        z + y
+    } else if z < y / 2.0 {
+        z
+    } else {
+        z - y
+    }
+}
+
+/// 角度（度）を `-180.0..=180.0` の範囲に正規化する。
+fn ang_normalize(x: f64) -> f64 {
+    let y = remainder(x, 360.0);
+    if y.abs() == 180.0 {
+        180_f64.copysign(x)
+    } else {
+        y
+    }
+}
+
+/// 角度の差 `y - x` を、丸め誤差を含まない和 `d + t` として返す。
+fn ang_diff(x: f64, y: f64) -> (f64, f64) {
+    let (d, t) = sum(remainder(-x, 360.0), remainder(y, 360.0));
+    let (d, t) = sum(remainder(d, 360.0), t);
+    let d = if d == 0.0 || d.abs() == 180.0 {
+        d.copysign(if t == 0.0 { y - x } else { -t })
+    } else {
+        d
+    };
+    (d, t)
+}
+
+/// 0 付近の小さな角度を 1/16 度の刻みに丸め、極付近の計算で生じる誤差を抑える。
+fn ang_round(x: f64) -> f64 {
+    let z = 1.0 / 16.0;
+    let y = x.abs();
+    let y = if y < z { z - (z - y) } else { y };
+    y.copysign(x)
+}
+
+/// 範囲外の緯度を `NaN` とする。
+fn lat_fix(x: f64) -> f64 {
+    if x.abs() > 90.0 { f64::NAN } else { x }
+}
+
+/// 角度（度）の正弦と余弦を、90 度の倍数で厳密な値となるように返す。
+fn sincosd(x: f64) -> (f64, f64) {
+    let r = x % 360.0;
+    let q = if r.is_nan() {
+        0
+    } else {
+        (r / 90.0).round() as i32
+    };
+    let (s, c) = (r - 90.0 * q as f64).to_radians().sin_cos();
+    let (s, c) = match q.rem_euclid(4) {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+    let s = if s == 0.0 { s.copysign(x) } else { s };
+    (s, c + 0.0)
+}
+
+/// `atan2(y, x)` を度で返す。
+fn atan2d(y: f64, x: f64) -> f64 {
+    let (mut x, mut y) = (x, y);
+    let mut q = 0;
+    if y.abs() > x.abs() {
+        std::mem::swap(&mut x, &mut y);
+        q = 2;
+    }
+    if x < 0.0 {
+        q += 1;
+        x = -x;
+    }
+    let ang = y.atan2(x).to_degrees();
+    match q {
+        1 => (if y >= 0.0 { 180.0 } else { -180.0 }) - ang,
+        2 => 90.0 - ang,
+        3 => -90.0 + ang,
+        _ => ang,
+    }
+}
@@ -0,0 +1,173 @@
+//! 緯度制限のない楕円体上の緯度・経度・高度
+//!
+//! [`Ecef`](crate::geometry::ecef::Ecef) から測地座標への変換を閉じた式で求め、
+//! 極付近や空間 ID の高度の範囲外の地点もそのまま保持します。空間 ID として扱う場合は、
+//! 範囲を検証するか範囲内に丸めて [`Coordinate`](crate::geometry::coordinate::Coordinate)
+//! へ変換します。
+
+use crate::{
+    error::Error,
+    geometry::{coordinate::Coordinate, ecef::Ecef, ellipsoid::Ellipsoid},
+};
+
+/// [`Coordinate`] の緯度の上限（度）
+const COORDINATE_MAX_LATITUDE: f64 = 85.0511;
+
+/// [`Coordinate`] の高度の上限（m）
+const COORDINATE_MAX_ALTITUDE: f64 = 33_554_432.0;
+
+/// 楕円体を基準とした緯度・経度・高度（楕円体高）
+///
+/// [`Coordinate`] と異なり、WEBメルカトルの緯度制限（±85.0511 度）や空間 ID の高度の範囲を
+/// 持たず、極を含むすべての地点を表せます。[`Ecef`] から変換した結果を失わずに保持し、
+/// 空間 ID として扱う段階で [`Coordinate`] へ変換します。
+///
+/// ```
+/// # use kasane_logic::geometry::{coordinate::Coordinate, ecef::Ecef, geodetic::Geodetic};
+/// // 北極点の上空 100m
+/// let pole = Ecef::new(0.0, 0.0, 6_356_752.314_245 + 100.0);
+/// let geodetic = Geodetic::from(pole);
+/// assert_eq!(geodetic.as_latitude(), 90.0);
+/// assert!((geodetic.as_altitude() - 100.0).abs() < 1e-6);
+///
+/// // Coordinate へは、範囲を検証して変換するか、範囲内に丸めて変換するかを選ぶ
+/// assert!(Coordinate::try_from(geodetic).is_err());
+/// let clamped = geodetic.to_coordinate_clamped().unwrap();
+/// assert_eq!(clamped.as_latitude(), 85.0511);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+}
+
+impl Geodetic {
+    /// 緯度・経度（度）と高度（m）から [`Geodetic`] を生成します。
+    ///
+    /// # 戻り値
+    /// * 緯度が `-90.0..=90.0` の範囲外の場合は [`Error::GeodeticLatitudeOutOfRange`] を返します
+    /// * 経度が `-180.0..=180.0` の範囲外の場合は [`Error::LongitudeOutOfRange`] を返します
+    /// * 高度が有限でない場合は [`Error::AltitudeNotFinite`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::geodetic::Geodetic;
+    /// let south_pole = Geodetic::new(-90.0, 0.0, 2_835.0).unwrap();
+    /// assert_eq!(south_pole.as_latitude(), -90.0);
+    ///
+    /// assert_eq!(
+    ///     Geodetic::new(90.5, 0.0, 0.0),
+    ///     Err(Error::GeodeticLatitudeOutOfRange { latitude: 90.5 })
+    /// );
+    /// assert!(matches!(
+    ///     Geodetic::new(0.0, 0.0, f64::NAN),
+    ///     Err(Error::AltitudeNotFinite { .. })
+    /// ));
+    /// ```
+    pub fn new(latitude: f64, longitude: f64, altitude: f64) -> Result<Geodetic, Error> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(Error::GeodeticLatitudeOutOfRange { latitude });
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(Error::LongitudeOutOfRange { longitude });
+        }
+        if !altitude.is_finite() {
+            return Err(Error::AltitudeNotFinite { altitude });
+        }
+        Ok(Geodetic {
+            latitude,
+            longitude,
+            altitude,
+        })
+    }
+
+    /// 範囲の検証を行わずに、緯度・経度（度）・高度（m）の配列 `[lat, lon, h]` から生成する。
+    pub(crate) fn from_unchecked([latitude, longitude, altitude]: [f64; 3]) -> Geodetic {
+        Geodetic {
+            latitude,
+            longitude,
+            altitude,
+        }
+    }
+
+    /// 緯度（度）を返します。
+    pub fn as_latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    /// 経度（度）を返します。
+    pub fn as_longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// 高度（楕円体高, m）を返します。
+    pub fn as_altitude(&self) -> f64 {
+        self.altitude
+    }
+
+    /// 指定された楕円体を基準として [`Ecef`] へ変換します。
+    pub fn to_ecef(self, ellipsoid: &Ellipsoid) -> Ecef {
+        ellipsoid.geodetic_to_ecef([self.latitude, self.longitude, self.altitude])
+    }
+
+    /// 緯度と高度を [`Coordinate`] で扱える範囲内に丸めて変換します。
+    ///
+    /// 極付近の点は緯度 ±85.0511 度の位置へ移動するため、元の位置から最大で 500km 程度
+    /// 離れます。範囲外の点を誤差として扱う場合は `Coordinate::try_from` を使用します。
+    ///
+    /// # 戻り値
+    /// * 緯度・経度・高度のいずれかが数値でない場合は、対応する `Error` を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::geodetic::Geodetic;
+    /// let satellite = Geodetic::new(35.0, 139.0, 40_000_000.0).unwrap();
+    /// let clamped = satellite.to_coordinate_clamped().unwrap();
+    /// assert_eq!(clamped.as_latitude(), 35.0);
+    /// assert_eq!(clamped.as_altitude(), 33_554_432.0);
+    /// ```
+    pub fn to_coordinate_clamped(self) -> Result<Coordinate, Error> {
+        Coordinate::new(
+            self.latitude
+                .clamp(-COORDINATE_MAX_LATITUDE, COORDINATE_MAX_LATITUDE),
+            self.longitude,
+            self.altitude
+                .clamp(-COORDINATE_MAX_ALTITUDE, COORDINATE_MAX_ALTITUDE),
+        )
+    }
+}
+
+impl From<Coordinate> for Geodetic {
+    fn from(value: Coordinate) -> Self {
+        Geodetic {
+            latitude: value.as_latitude(),
+            longitude: value.as_longitude(),
+            altitude: value.as_altitude(),
+        }
+    }
+}
+
+/// WGS-84 を基準として変換します。他の楕円体を基準とする場合は [`Ecef::to_geodetic`] を使用します。
+impl From<Ecef> for Geodetic {
+    fn from(value: Ecef) -> Self {
+        value.to_geodetic(&Ellipsoid::WGS84)
+    }
+}
+
+/// WGS-84 を基準として変換します。他の楕円体を基準とする場合は [`Geodetic::to_ecef`] を使用します。
+impl From<Geodetic> for Ecef {
+    fn from(value: Geodetic) -> Self {
+        value.to_ecef(&Ellipsoid::WGS84)
+    }
+}
+
+/// 範囲を検証して [`Coordinate`] へ変換します。
+///
+/// 緯度が ±85.0511 度を超える場合や、高度が空間 ID の範囲外の場合は `Error` を返します。
+impl TryFrom<Geodetic> for Coordinate {
+    type Error = Error;
+
+    fn try_from(value: Geodetic) -> Result<Self, Self::Error> {
+        Coordinate::new(value.latitude, value.longitude, value.altitude)
+    }
+}
@@ -0,0 +1,321 @@
+//! ジオイドモデルと高さの基準の変換
+//!
+//! [`Coordinate`](crate::geometry::coordinate::Coordinate) の高度は、[`Ecef`](crate::geometry::ecef::Ecef)
+//! への変換では楕円体高として扱われます。標高（ジオイドからの高さ）で表されたデータは、
+//! ジオイド高を加えて楕円体高に変換してから扱う必要があります。
+//!
+//! 楕円体高 `h`・標高 `H`・ジオイド高 `N` の間には `h = H + N` の関係があります。
+
+use std::path::Path;
+
+use crate::{error::Error, geometry::helpers::read_grid_file};
+
+/// 緯度・経度からジオイド高を求めるジオイドモデル
+pub trait Geoid {
+    /// 緯度・経度（度）におけるジオイド高（楕円体面からジオイド面までの高さ, m）を返します。
+    fn undulation(&self, latitude: f64, longitude: f64) -> Result<f64, Error>;
+
+    /// 緯度・経度の範囲 `[南端, 北端]`・`[西端, 東端]`（度）におけるジオイド高の下限と上限を返します。
+    ///
+    /// 範囲内のすべての地点のジオイド高を含む値を返す必要があります。
+    /// 範囲内にジオイド高が求まらない地点を含む場合は `Error` を返します。
+    fn undulation_bounds(&self, latitude: [f64; 2], longitude: [f64; 2])
+    -> Result<[f64; 2], Error>;
+}
+
+/// 等間隔の格子点のジオイド高から双一次補間を行うジオイドモデル
+///
+/// 国土地理院のジオイド・モデル（GSIGEO2011 など）の ASCII 形式と、
+/// EGM96・EGM2008 の `.GRD` 形式のファイルを読み込めます。
+///
+/// ```
+/// # use kasane_logic::geometry::geoid::{Geoid, GridGeoid};
+/// // 南西端 35°N 139°E、緯度 1 分・経度 1.5 分間隔の 2 × 3 の格子
+/// let asc = "\
+///   35.00000 139.00000 0.016667 0.025000 2 3 1 ver2.2
+///   36.0000  36.2000  36.4000
+///   36.1000  36.3000  999.0000
+/// ";
+/// let geoid = GridGeoid::from_gsigeo_str(asc).unwrap();
+///
+/// assert!((geoid.undulation(35.0, 139.0).unwrap() - 36.0).abs() < 1e-9);
+/// // 4 つの格子点の中央
+/// let n = geoid.undulation(35.0 + 1.0 / 120.0, 139.0125).unwrap();
+/// assert!((n - 36.15).abs() < 1e-4);
+///
+/// // 欠測値（999.0000）を含む範囲や格子の範囲外
+/// assert!(geoid.undulation(35.01, 139.04).is_err());
+/// assert!(geoid.undulation(34.9, 139.0).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridGeoid {
+    /// 南西端の格子点の緯度・経度（度）
+    origin: [f64; 2],
+    /// 緯度方向・経度方向の格子の間隔（度）
+    step: [f64; 2],
+    rows: usize,
+    columns: usize,
+    /// 南から北へ、各行は西から東へ並べたジオイド高（m）。欠測値は `NaN`
+    values: Vec<f64>,
+}
+
+/// GSIGEO の ASCII 形式で欠測を表す値
+const GSIGEO_MISSING: f64 = 999.0;
+
+impl GridGeoid {
+    /// 国土地理院のジオイド・モデルの ASCII 形式のファイルを読み込みます。
+    ///
+    /// # 戻り値
+    /// * ファイルを読み込めない場合は [`Error::GridFileRead`] を返します
+    /// * 解釈できない行がある場合は [`Error::GridFileParse`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::geoid::GridGeoid;
+    /// let missing = GridGeoid::from_gsigeo_file("/nonexistent/gsigeo2011_ver2_2.asc");
+    /// assert!(matches!(missing, Err(Error::GridFileRead { .. })));
+    ///
+    /// // 格子点の数が見出しと一致しない
+    /// let short = GridGeoid::from_gsigeo_str("35.0 139.0 0.016667 0.025 2 2 1 ver2.2\n36.0 36.1\n36.2\n");
+    /// assert_eq!(short.unwrap_err(), Error::GridFileParse { line: 3 });
+    /// ```
+    pub fn from_gsigeo_file(path: impl AsRef<Path>) -> Result<GridGeoid, Error> {
+        GridGeoid::from_gsigeo_str(&read_grid_file(path.as_ref())?)
+    }
+
+    /// 国土地理院のジオイド・モデルの ASCII 形式の文字列から読み込みます。
+    ///
+    /// 1 行目の見出しは、南西端の緯度・経度、緯度・経度の間隔（度）、緯度方向・経度方向の
+    /// 格子点の数の順に並びます。2 行目以降は南から北へ、各緯度では西から東へジオイド高が並び、
+    /// `999.0000` は欠測値として扱います。
+    pub fn from_gsigeo_str(text: &str) -> Result<GridGeoid, Error> {
+        let (header, values) = parse_grid(text)?;
+        let [south, west, lat_step, lon_step, rows, columns] = header;
+        let values = values
+            .into_iter()
+            .map(|v| if v >= GSIGEO_MISSING { f64::NAN } else { v })
+            .collect();
+        GridGeoid::from_parts(
+            [south, west],
+            [lat_step, lon_step],
+            [rows, columns],
+            values,
+            text,
+        )
+    }
+
+    /// EGM96・EGM2008 の `.GRD` 形式のファイルを読み込みます。
+    ///
+    /// # 戻り値
+    /// * ファイルを読み込めない場合は [`Error::GridFileRead`] を返します
+    /// * 解釈できない行がある場合は [`Error::GridFileParse`] を返します
+    pub fn from_egm_grd_file(path: impl AsRef<Path>) -> Result<GridGeoid, Error> {
+        GridGeoid::from_egm_grd_str(&read_grid_file(path.as_ref())?)
+    }
+
+    /// EGM96・EGM2008 の `.GRD` 形式の文字列から読み込みます。
+    ///
+    /// 1 行目の見出しは南端・北端の緯度、西端・東端の経度、緯度・経度の間隔（度）の順に並びます。
+    /// 2 行目以降は北から南へ、各緯度では西から東へジオイド高が並びます。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::geoid::{Geoid, GridGeoid};
+    /// // 経度 0°〜360° の全球の格子。西経は 360° を加えて参照する
+    /// let grd = "\
+    /// -90.0 90.0 0.0 360.0 90.0 180.0
+    /// 14.9 14.9 14.9
+    /// 17.2 -61.4 17.2
+    /// -29.5 -29.5 -29.5
+    /// ";
+    /// let geoid = GridGeoid::from_egm_grd_str(grd).unwrap();
+    ///
+    /// assert!((geoid.undulation(0.0, 180.0).unwrap() + 61.4).abs() < 1e-9);
+    /// assert!((geoid.undulation(0.0, -90.0).unwrap() + 22.1).abs() < 1e-9);
+    /// assert!((geoid.undulation(90.0, 10.0).unwrap() - 14.9).abs() < 1e-9);
+    /// ```
+    pub fn from_egm_grd_str(text: &str) -> Result<GridGeoid, Error> {
+        let (header, values) = parse_grid(text)?;
+        let [south, north, west, east, lat_step, lon_step] = header;
+        let rows = ((north - south) / lat_step).round() + 1.0;
+        let columns = ((east - west) / lon_step).round() + 1.0;
+
+        // 北から南へ並んだ行を、南から北への順に並べ替える
+        let width = columns.max(1.0) as usize;
+        let values = values.chunks(width).rev().flatten().copied().collect();
+        GridGeoid::from_parts(
+            [south, west],
+            [lat_step, lon_step],
+            [rows, columns],
+            values,
+            text,
+        )
+    }
+
+    /// 格子点の個数を返します。
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// 格子点を持たない場合に `true` を返します。
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// 見出しの値と格子点の値から生成する。
+    ///
+    /// 格子の大きさが見出しと一致しない場合は、最終行の位置で [`Error::GridFileParse`] を返す。
+    fn from_parts(
+        origin: [f64; 2],
+        step: [f64; 2],
+        [rows, columns]: [f64; 2],
+        values: Vec<f64>,
+        text: &str,
+    ) -> Result<GridGeoid, Error> {
+        let valid = step.iter().all(|s| *s > 0.0)
+            && rows >= 2.0
+            && columns >= 2.0
+            && rows * columns == values.len() as f64;
+        if !valid {
+            return Err(Error::GridFileParse {
+                line: text.lines().count(),
+            });
+        }
+        Ok(GridGeoid {
+            origin,
+            step,
+            rows: rows as usize,
+            columns: columns as usize,
+            values,
+        })
+    }
+}
+
+impl Geoid for GridGeoid {
+    /// 周囲の 4 つの格子点から双一次補間したジオイド高を返します。
+    ///
+    /// 格子の範囲外の場合や、周囲の格子点に欠測値を含む場合は [`Error::GridOutOfRange`] を返します。
+    fn undulation(&self, latitude: f64, longitude: f64) -> Result<f64, Error> {
+        let out_of_range = Error::GridOutOfRange {
+            latitude,
+            longitude,
+        };
+        // 経度 0°〜360° の格子で西経を参照できるようにする
+        let lon = if longitude < self.origin[1] {
+            longitude + 360.0
+        } else {
+            longitude
+        };
+        let u = (latitude - self.origin[0]) / self.step[0];
+        let v = (lon - self.origin[1]) / self.step[1];
+        let (last_row, last_column) = ((self.rows - 1) as f64, (self.columns - 1) as f64);
+        if !(0.0..=last_row).contains(&u) || !(0.0..=last_column).contains(&v) {
+            return Err(out_of_range);
+        }
+
+        // 北端・東端の格子点上では 1 つ内側の格子を用いる
+        let i = (u.floor() as usize).min(self.rows - 2);
+        let j = (v.floor() as usize).min(self.columns - 2);
+        let (s, t) = (u - i as f64, v - j as f64);
+
+        let node = |di: usize, dj: usize| self.values[(i + di) * self.columns + j + dj];
+        let [sw, se, nw, ne] = [node(0, 0), node(0, 1), node(1, 0), node(1, 1)];
+        let south = sw + (se - sw) * t;
+        let north = nw + (ne - nw) * t;
+        let undulation = south + (north - south) * s;
+        if undulation.is_nan() {
+            return Err(out_of_range);
+        }
+        Ok(undulation)
+    }
+
+    /// 範囲と重なる格子の格子点のジオイド高の最小値と最大値を返します。
+    ///
+    /// 双一次補間の値は周囲の格子点の値の間にあるため、範囲内のジオイド高はこの間に収まります。
+    /// 範囲が格子の外側にかかる場合や、欠測値を含む場合は [`Error::GridOutOfRange`] を返します。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::geoid::{Geoid, GridGeoid};
+    /// let geoid = GridGeoid::from_gsigeo_str(
+    ///     "35.0 139.0 0.5 0.5 3 2 1 ver2.2\n36.0 36.2\n36.4 36.6\n36.8 999.0\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// // 南側の格子のみにかかる範囲
+    /// assert_eq!(geoid.undulation_bounds([35.1, 35.2], [139.1, 139.2]).unwrap(), [36.0, 36.6]);
+    /// // 欠測値を含む格子にかかる範囲や、格子の範囲外にかかる範囲
+    /// assert!(geoid.undulation_bounds([35.6, 35.7], [139.1, 139.2]).is_err());
+    /// assert!(geoid.undulation_bounds([34.9, 35.2], [139.1, 139.2]).is_err());
+    /// ```
+    fn undulation_bounds(
+        &self,
+        [south, north]: [f64; 2],
+        [west, east]: [f64; 2],
+    ) -> Result<[f64; 2], Error> {
+        let out_of_range = Error::GridOutOfRange {
+            latitude: south,
+            longitude: west,
+        };
+        let shift = |lon: f64| {
+            if lon < self.origin[1] {
+                lon + 360.0
+            } else {
+                lon
+            }
+        };
+        let (west, east) = (shift(west), shift(east));
+        let u = [south, north].map(|lat| (lat - self.origin[0]) / self.step[0]);
+        let v = [west, east].map(|lon| (lon - self.origin[1]) / self.step[1]);
+        let (last_row, last_column) = ((self.rows - 1) as f64, (self.columns - 1) as f64);
+        let inside =
+            |[start, end]: [f64; 2], last: f64| (0.0..=end).contains(&start) && end <= last;
+        if !inside(u, last_row) || !inside(v, last_column) {
+            return Err(out_of_range);
+        }
+
+        // 範囲と重なる格子の格子点（北端・東端の格子点上では 1 つ内側の格子を用いる）
+        let rows = (u[0].floor() as usize).min(self.rows - 2)..=(u[1].ceil() as usize).max(1);
+        let columns = (v[0].floor() as usize).min(self.columns - 2)..=(v[1].ceil() as usize).max(1);
+
+        let mut bounds = [f64::INFINITY, f64::NEG_INFINITY];
+        for i in rows {
+            for j in columns.clone() {
+                let value = self.values[i * self.columns + j];
+                if value.is_nan() {
+                    return Err(out_of_range);
+                }
+                bounds = [bounds[0].min(value), bounds[1].max(value)];
+            }
+        }
+        Ok(bounds)
+    }
+}
+
+/// 空行を除く 1 行目の先頭 `N` 個の数値を見出しとし、以降の行の数値をすべて格子点の値として返す。
+fn parse_grid<const N: usize>(text: &str) -> Result<([f64; N], Vec<f64>), Error> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (index, header) = lines.next().ok_or(Error::GridFileParse { line: 1 })?;
+    let fields: Vec<f64> = header
+        .split_whitespace()
+        .take(N)
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| Error::GridFileParse { line: index + 1 })?;
+    let header: [f64; N] = fields
+        .try_into()
+        .map_err(|_| Error::GridFileParse { line: index + 1 })?;
+
+    let mut values = Vec::new();
+    for (index, line) in lines {
+        for field in line.split_whitespace() {
+            let value = field
+                .parse()
+                .map_err(|_| Error::GridFileParse { line: index + 1 })?;
+            values.push(value);
+        }
+    }
+    Ok((header, values))
+}
@@ -1 +1,270 @@
+use std::{f64::consts::PI, fs, path::Path};
 
+use crate::{
+    error::Error,
+    geometry::{
+        constants::{WGS84_A, WGS84_B, WGS84_E2},
+        coordinate::Coordinate,
+        ecef::Ecef,
+    },
+    spatial_id::{
+        SpatialId,
+        constants::{F_MAX, F_MIN},
+        helpers::{altitude, latitude},
+        single::SingleId,
+    },
+};
+
+/// 座標を、指定されたズームレベルにおける連続値のインデックス空間 `[f, x, y]` へ変換する。
+///
+/// 小数点以下を切り捨てないため、戻り値の `floor()` が所属する空間 ID のインデックスとなる。
+pub(crate) fn coordinate_to_matrix(p: Coordinate, z: u8) -> [f64; 3] {
+    geodetic_to_matrix(p.as_latitude(), p.as_longitude(), p.as_altitude(), z)
+}
+
+/// 緯度・経度（度）・高度（m）を、範囲の検証を行わずにインデックス空間 `[f, x, y]` へ変換する。
+///
+/// 経度が `-180.0..=180.0` を超える場合、X は `0..2^z` の外側へ連続的に延長される。
+pub(crate) fn geodetic_to_matrix(lat: f64, lon: f64, alt: f64, z: u8) -> [f64; 3] {
+    // 空間idの高さはz=25でちょうど1mになるように定義されている
+    let factor = 2_f64.powi(z as i32 - 25);
+    let f = factor * alt;
+
+    let n = 2u64.pow(z as u32) as f64;
+    let x = (lon + 180.0) / 360.0 * n;
+
+    let lat_rad = lat.to_radians();
+    let y = (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * n;
+    [f, x, y]
+}
+
+/// 角度（度）を `-180.0..180.0` の範囲へ正規化する。
+pub(crate) fn wrap_longitude(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+pub(crate) fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+pub(crate) fn ecef_array(e: Ecef) -> [f64; 3] {
+    [e.as_x(), e.as_y(), e.as_z()]
+}
+
+/// 点 `p` から線分 `ab` までの距離を返す。
+pub(crate) fn distance_to_segment(p: [f64; 3], a: [f64; 3], b: [f64; 3]) -> f64 {
+    let ab = sub(b, a);
+    let len2 = dot(ab, ab);
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (dot(sub(p, a), ab) / len2).clamp(0.0, 1.0)
+    };
+    norm(sub(
+        p,
+        [a[0] + ab[0] * t, a[1] + ab[1] * t, a[2] + ab[2] * t],
+    ))
+}
+
+/// 点 `p` から三角形 `abc` までの距離を返す。
+pub(crate) fn distance_to_triangle(p: [f64; 3], a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> f64 {
+    let n = cross(sub(b, a), sub(c, a));
+    let n_len = norm(n);
+
+    if n_len > 0.0 {
+        // 平面への射影点が三角形の内部にあれば、平面までの距離が最短距離となる
+        let inside = [(a, b), (b, c), (c, a)]
+            .iter()
+            .all(|&(s, e)| dot(cross(sub(e, s), sub(p, s)), n) >= 0.0);
+        if inside {
+            return dot(sub(p, a), n).abs() / n_len;
+        }
+    }
+
+    distance_to_segment(p, a, b)
+        .min(distance_to_segment(p, b, c))
+        .min(distance_to_segment(p, c, a))
+}
+
+/// 空間 ID を内包する ECEF 上の球（中心と半径）を返す。
+///
+/// 8 頂点の重心を中心とし、頂点までの最大距離に、緯線・経線の弧が弦から膨らむ量を加えて半径とする。
+pub(crate) fn cell_bound(id: &SingleId) -> ([f64; 3], f64) {
+    let (vertices, sagitta) = cell_vertices(id);
+
+    let mut center = [0.0; 3];
+    for v in &vertices {
+        center = [center[0] + v[0], center[1] + v[1], center[2] + v[2]];
+    }
+    let center = center.map(|c| c / 8.0);
+
+    let radius = vertices
+        .iter()
+        .map(|v| norm(sub(*v, center)))
+        .fold(0.0, f64::max);
+
+    (center, radius + sagitta)
+}
+
+/// 空間 ID の 8 頂点の ECEF 座標と、緯線・経線の弧が頂点を結ぶ弦から膨らむ量の上限を返す。
+///
+/// 空間 ID の各点は、8 頂点の凸包からこの量以内にある。
+pub(crate) fn cell_vertices(id: &SingleId) -> ([[f64; 3]; 8], f64) {
+    let vertices = id.vertices().map(|v| ecef_array(v.into()));
+
+    // 楕円体の曲率半径の上限（極での子午線曲率半径）に高度を加えたもの
+    let h = vertices_altitude_bound(id);
+    let r_max = WGS84_A / (1.0 - WGS84_E2).sqrt() + h;
+
+    let n = 2_f64.powi(id.as_z() as i32);
+    let d_lon = (2.0 * PI / n).min(PI);
+    let lat_top = latitude(id.as_y() as f64, id.as_z()).to_radians();
+    let lat_bottom = latitude(id.as_y() as f64 + 1.0, id.as_z()).to_radians();
+    let d_lat = (lat_top - lat_bottom).abs();
+
+    let sagitta = r_max * (1.0 - (d_lon / 2.0).cos()) + r_max * (1.0 - (d_lat / 2.0).cos());
+
+    (vertices, sagitta)
+}
+
+fn vertices_altitude_bound(id: &SingleId) -> f64 {
+    let h0 = altitude(id.as_f() as f64, id.as_z());
+    let h1 = altitude(id.as_f() as f64 + 1.0, id.as_z());
+    h0.abs().max(h1.abs())
+}
+
+/// WEBメルカトルで表現できる緯度の上限（度）
+pub(crate) const MERCATOR_MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// 測地緯度と地心緯度の差の上限（度）に余裕を持たせた値
+const GEOCENTRIC_LATITUDE_MARGIN: f64 = 0.2;
+
+/// 中心から半径 `radius`（m）以内の点がとり得る X・Y インデックスの範囲
+pub(crate) struct TileBounds {
+    /// 経度 ±180° をまたぐ場合は 2 つの範囲に分割される
+    pub(crate) x: Vec<[u32; 2]>,
+    pub(crate) y: [u32; 2],
+}
+
+impl TileBounds {
+    /// 範囲内の `(x, y)` の組をすべて列挙する。
+    pub(crate) fn columns(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let [y_min, y_max] = self.y;
+        self.x.iter().flat_map(move |&[x_min, x_max]| {
+            (x_min..=x_max).flat_map(move |x| (y_min..=y_max).map(move |y| (x, y)))
+        })
+    }
+}
+
+/// 中心から半径 `radius`（m）以内の点を含む、ズームレベル `z` における X・Y インデックスの範囲を求める。
+///
+/// 地心から見た球の視半径をもとに緯度・経度の範囲を求めるため、球が極を含む場合や
+/// 経度 ±180° をまたぐ場合でも範囲が正しく求まる。
+/// 範囲がWEBメルカトルの緯度範囲と重ならない場合は `None` を返す。
+pub(crate) fn tile_bounds(center: Coordinate, radius: f64, z: u8) -> Option<TileBounds> {
+    if radius.is_nan() || radius < 0.0 {
+        return None;
+    }
+
+    let n = 1_i64 << z;
+    let distance = norm(ecef_array(center.into()));
+    let lat = center.as_latitude();
+
+    // 地心から見た球の視半径（度）
+    let theta = if radius >= distance {
+        180.0
+    } else {
+        (radius / distance).asin().to_degrees() + GEOCENTRIC_LATITUDE_MARGIN
+    };
+
+    let lat_max = lat + theta;
+    let lat_min = lat - theta;
+    if lat_min > MERCATOR_MAX_LATITUDE || lat_max < -MERCATOR_MAX_LATITUDE {
+        return None;
+    }
+
+    // 球面上の円の経度方向の半幅
+    let half_width = if lat_max >= 90.0 || lat_min <= -90.0 {
+        None
+    } else {
+        let ratio = theta.to_radians().sin() / lat.to_radians().cos();
+        (ratio < 1.0).then(|| ratio.asin().to_degrees())
+    };
+
+    let x = match half_width {
+        Some(half_width) => {
+            let x_of = |lon: f64| ((lon + 180.0) / 360.0 * n as f64).floor() as i64;
+            let x_min = x_of(center.as_longitude() - half_width);
+            let x_max = x_of(center.as_longitude() + half_width);
+            if x_max - x_min + 1 >= n {
+                vec![[0, (n - 1) as u32]]
+            } else if x_min < 0 {
+                vec![[0, x_max as u32], [(x_min + n) as u32, (n - 1) as u32]]
+            } else if x_max >= n {
+                vec![[0, (x_max - n) as u32], [x_min as u32, (n - 1) as u32]]
+            } else {
+                vec![[x_min as u32, x_max as u32]]
+            }
+        }
+        None => vec![[0, (n - 1) as u32]],
+    };
+
+    let y_of = |lat: f64| {
+        let y = geodetic_to_matrix(lat, 0.0, 0.0, z)[2].floor() as i64;
+        y.clamp(0, n - 1) as u32
+    };
+    let y = [
+        y_of(lat_max.min(MERCATOR_MAX_LATITUDE)),
+        y_of(lat_min.max(-MERCATOR_MAX_LATITUDE)),
+    ];
+
+    Some(TileBounds { x, y })
+}
+
+/// 高度（m）をズームレベル `z` における F インデックスに変換する。
+///
+/// 範囲外の高度は `F_MIN[z]..=F_MAX[z]` に丸める。
+pub(crate) fn altitude_to_f(altitude: f64, z: u8) -> i32 {
+    let f = (altitude * 2_f64.powi(z as i32 - 25)).floor() as i64;
+    f.clamp(F_MIN[z as usize] as i64, F_MAX[z as usize] as i64) as i32
+}
+
+/// 測地座標から ECEF への変換が一意となる高度の下限（m）
+///
+/// 楕円体の曲率半径の最小値 b²/a より深い点は地球の中心付近で折り返し、
+/// 地表付近の別の地点と同じ位置を表してしまう。
+pub(crate) const FOLDING_ALTITUDE: f64 = -(WGS84_B * WGS84_B / WGS84_A);
+
+/// 空間 ID 全体が [`FOLDING_ALTITUDE`] より深いかを返す。
+pub(crate) fn is_folded(id: &SingleId) -> bool {
+    altitude(id.as_f() as f64 + 1.0, id.as_z()) <= FOLDING_ALTITUDE
+}
+
+/// ズームレベル `z` で [`FOLDING_ALTITUDE`] より深い部分を含まない空間 ID のうち、最も低い F を返す。
+pub(crate) fn lowest_unfolded_f(z: u8) -> i32 {
+    altitude_to_f(FOLDING_ALTITUDE, z)
+}
+
+/// 格子状のデータ（補正パラメータ・ジオイド高・標高）のファイルを文字列として読み込む。
+pub(crate) fn read_grid_file(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|error| Error::GridFileRead {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    })
+}
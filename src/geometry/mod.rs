@@ -3,17 +3,56 @@
 //! 本モジュールでは、距離、半径、高さなどの長さに関する値を、
 //! 特に明記しない限りメートル（m）単位で扱います。
 
+/// 高度の基準と、基準の間の変換
+pub mod altitude;
+
 /// 地理空間座標の計算で使用される各種の代表的な定数
 pub mod constants;
 
 /// 緯度・経度・高度で定義される `Coordinate` 型
 pub mod coordinate;
 
+/// 測地系の間の座標変換
+pub mod datum;
+
 /// 地心直交座標系で定義される `Ecef` 型
 pub mod ecef;
 
+/// 地球を近似する回転楕円体を表す `Ellipsoid` 型
+pub mod ellipsoid;
+
+/// 局所接平面座標系（ENU・NED）で定義される `Enu` 型と `EnuFrame` 型
+pub mod enu;
+
+/// カメラの視錐台による空間 ID の選別
+pub mod frustum;
+
+/// 楕円体上の測地線の逆問題・順問題を解く `Geodesic` 型
+pub mod geodesic;
+
+/// 緯度制限のない楕円体上の緯度・経度・高度
+pub mod geodetic;
+
+/// ジオイドモデルと、楕円体高と標高の変換
+pub mod geoid;
+
 /// 地理空間系全体で共通して使用されるヘルパー関数群
 pub(crate) mod helpers;
 
+/// 平面直角座標系（第 I 系〜第 XIX 系）で定義される `PlaneRectangular` 型
+pub mod plane_rectangular;
+
 /// 線分、三角形、円などの幾何形状から空間IDへの変換
 pub mod shapes;
+
+/// 数値標高モデルによる地表面の標高
+pub mod terrain;
+
+/// 楕円体の横メルカトル図法
+pub(crate) mod transverse_mercator;
+
+/// UTM 座標系と MGRS による座標の変換
+pub mod utm;
+
+/// 空間 ID の集合を障害物とした見通しの判定
+pub mod visibility;
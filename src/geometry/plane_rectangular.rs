@@ -0,0 +1,165 @@
+//! 平面直角座標系（第 I 系〜第 XIX 系）
+//!
+//! 国土交通省告示で定められた 19 の系ごとの原点と縮尺係数を用いて、
+//! 緯度・経度を横メルカトル図法で平面に投影した座標と
+//! [`Coordinate`](crate::geometry::coordinate::Coordinate) を相互に変換します。
+
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate, ellipsoid::Ellipsoid, transverse_mercator::TransverseMercator,
+    },
+    spatial_id::single::SingleId,
+};
+
+/// 平面直角座標系の各系の原点の緯度・経度（度）
+const ORIGINS: [[f64; 2]; 19] = [
+    [33.0, 129.5],
+    [33.0, 131.0],
+    [36.0, 132.0 + 10.0 / 60.0],
+    [33.0, 133.5],
+    [36.0, 134.0 + 20.0 / 60.0],
+    [36.0, 136.0],
+    [36.0, 137.0 + 10.0 / 60.0],
+    [36.0, 138.5],
+    [36.0, 139.0 + 50.0 / 60.0],
+    [40.0, 140.0 + 50.0 / 60.0],
+    [44.0, 140.25],
+    [44.0, 142.25],
+    [44.0, 144.25],
+    [26.0, 142.0],
+    [26.0, 127.5],
+    [26.0, 124.0],
+    [26.0, 131.0],
+    [20.0, 136.0],
+    [26.0, 154.0],
+];
+
+/// 平面直角座標系の原点における縮尺係数
+const SCALE: f64 = 0.9999;
+
+/// 平面直角座標系（第 I 系〜第 XIX 系）における座標を表します。
+///
+/// 測量や CAD のデータで用いられる座標系で、GRS80 楕円体上の日本測地系 2011 の緯度・経度を、
+/// 系ごとの原点を中心とする横メルカトル図法で平面に投影したものです。
+/// `x` は原点から北向き、`y` は原点から東向きの距離（m）です。
+///
+/// 日本測地系 2011 の緯度・経度は WGS-84 と同一とみなして [`Coordinate`] と変換します。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::plane_rectangular::PlaneRectangular;
+/// // 第 IX 系の原点（北緯 36°、東経 139°50′）
+/// let origin: Coordinate = PlaneRectangular::new(9, 0.0, 0.0, 0.0).unwrap().try_into().unwrap();
+/// assert!((origin.as_latitude() - 36.0).abs() < 1e-12);
+/// assert!((origin.as_longitude() - (139.0 + 50.0 / 60.0)).abs() < 1e-12);
+///
+/// // 東京駅
+/// let station = Coordinate::new(35.681236, 139.767125, 3.0).unwrap();
+/// let plane = PlaneRectangular::from_coordinate(&station, 9).unwrap();
+/// assert!((plane.as_x() - -35_363.238).abs() < 1e-3);
+/// assert!((plane.as_y() - -5_992.920).abs() < 1e-3);
+///
+/// let back = plane.to_coordinate().unwrap();
+/// assert!(back.geodesic_distance(&station) < 1e-6);
+/// assert_eq!(back.as_altitude(), 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaneRectangular {
+    zone: u8,
+    x: f64,
+    y: f64,
+    altitude: f64,
+}
+
+impl PlaneRectangular {
+    /// 系の番号（1〜19）・北向きの座標 `x`（m）・東向きの座標 `y`（m）・高度（m）から
+    /// [`PlaneRectangular`] を生成します。
+    ///
+    /// # 戻り値
+    /// * 系の番号が `1..=19` の範囲外の場合は [`Error::ZoneOutOfRange`] を返します
+    pub fn new(zone: u8, x: f64, y: f64, altitude: f64) -> Result<PlaneRectangular, Error> {
+        if !(1..=19).contains(&zone) {
+            return Err(Error::ZoneOutOfRange { zone });
+        }
+        Ok(PlaneRectangular {
+            zone,
+            x,
+            y,
+            altitude,
+        })
+    }
+
+    /// [`Coordinate`] を、指定された系の [`PlaneRectangular`] へ変換します。
+    ///
+    /// 系の範囲外の座標も変換できますが、原点から離れるほどひずみが大きくなります。
+    pub fn from_coordinate(coordinate: &Coordinate, zone: u8) -> Result<PlaneRectangular, Error> {
+        let [x, y] = projection(zone)?.forward(coordinate.as_latitude(), coordinate.as_longitude());
+        PlaneRectangular::new(zone, x, y, coordinate.as_altitude())
+    }
+
+    /// 系の番号を返します。
+    pub fn as_zone(&self) -> u8 {
+        self.zone
+    }
+
+    /// 原点から北向きの座標（m）を返します。
+    pub fn as_x(&self) -> f64 {
+        self.x
+    }
+
+    /// 原点から東向きの座標（m）を返します。
+    pub fn as_y(&self) -> f64 {
+        self.y
+    }
+
+    /// 高度（m）を返します。
+    pub fn as_altitude(&self) -> f64 {
+        self.altitude
+    }
+
+    /// [`Coordinate`] へ変換します。
+    ///
+    /// 変換後の座標が空間 ID 上で扱える範囲外の場合は、対応する `Error` を返します。
+    pub fn to_coordinate(self) -> Result<Coordinate, Error> {
+        let [latitude, longitude] = projection(self.zone)?.inverse(self.x, self.y);
+        Coordinate::new(latitude, longitude, self.altitude)
+    }
+
+    /// 指定されたズームレベルの [`SingleId`] へ変換します。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::plane_rectangular::PlaneRectangular;
+    /// let plane = PlaneRectangular::new(9, -35_363.238, -5_992.920, 3.0).unwrap();
+    /// let id = plane.to_single_id(25).unwrap();
+    ///
+    /// let station = Coordinate::new(35.681236, 139.767125, 3.0).unwrap();
+    /// assert_eq!(id.as_f(), 3);
+    /// assert_eq!(id, station.to_single_id(25));
+    /// ```
+    pub fn to_single_id(self, z: u8) -> Result<SingleId, Error> {
+        Ok(self.to_coordinate()?.to_single_id(z))
+    }
+}
+
+impl TryFrom<PlaneRectangular> for Coordinate {
+    type Error = Error;
+
+    fn try_from(value: PlaneRectangular) -> Result<Self, Self::Error> {
+        value.to_coordinate()
+    }
+}
+
+/// 系の番号に対応する横メルカトル図法を返す。
+fn projection(zone: u8) -> Result<TransverseMercator, Error> {
+    let [latitude, longitude] = *ORIGINS
+        .get((zone as usize).wrapping_sub(1))
+        .ok_or(Error::ZoneOutOfRange { zone })?;
+    Ok(TransverseMercator::new(
+        &Ellipsoid::GRS80,
+        SCALE,
+        latitude,
+        longitude,
+    ))
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+
+use crate::{
+    error::Error,
+    geometry::{
+        altitude::{AltitudeModels, AltitudeReference},
+        coordinate::Coordinate,
+        geoid::Geoid,
+        shapes::{Relation, Shape, polygon::Polygon},
+        terrain::Terrain,
+    },
+    spatial_id::{helpers, single::SingleId},
+};
+
+/// WEBメルカトル平面上の多角形を底面とし、地表面から高さ `lower` 以上 `upper` 以下（m）の
+/// 範囲を持つ立体
+///
+/// 「地表から 150m まで」のように対地高度で定められた空域を表します。
+/// 地表面の標高は [`Terrain`] から求め、[`Geoid`] のジオイド高を加えて楕円体高に直します。
+/// 空間 ID と [`AboveGround::contains`] に与える座標の高度は、他の立体と同じく楕円体高として扱います。
+/// 地表面の標高やジオイド高が求まらない地点は立体に含まれません。
+///
+/// ```
+/// # use kasane_logic::error::Error;
+/// # use kasane_logic::geometry::altitude::{AltitudeModels, AltitudeReference};
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::geoid::GridGeoid;
+/// # use kasane_logic::geometry::shapes::{Mode, Shape, above_ground::AboveGround};
+/// # use kasane_logic::geometry::terrain::Terrain;
+/// // 北側が標高 100m、南側が標高 0m の斜面
+/// let terrain = Terrain::from_esri_ascii_str(
+///     "ncols 2\nnrows 2\nxllcorner 139.0\nyllcorner 35.0\ncellsize 0.01\n100 100\n0 0\n",
+/// )
+/// .unwrap();
+/// // ジオイド高 36m
+/// let geoid = GridGeoid::from_gsigeo_str(
+///     "35.0 139.0 0.5 0.5 2 2 1 ver2.2\n36.0 36.0\n36.0 36.0\n",
+/// )
+/// .unwrap();
+/// let models = AltitudeModels::new(Some(&geoid), Some(&terrain));
+/// let footprint = vec![
+///     Coordinate::new(35.005, 139.005, 0.0).unwrap(),
+///     Coordinate::new(35.005, 139.015, 0.0).unwrap(),
+///     Coordinate::new(35.015, 139.015, 0.0).unwrap(),
+///     Coordinate::new(35.015, 139.005, 0.0).unwrap(),
+/// ];
+/// // 地表から 50m まで
+/// let airspace = AboveGround::new(footprint.clone(), &models, 0.0, 50.0).unwrap();
+///
+/// // 標高 120m は北側では地表から 30m、南側では地表から 110m
+/// let north = Coordinate::new(35.014, 139.01, 120.0).unwrap();
+/// let south = Coordinate::new(35.006, 139.01, 120.0).unwrap();
+/// let ellipsoidal = |coordinate: &Coordinate| {
+///     models
+///         .convert(coordinate, AltitudeReference::Orthometric, AltitudeReference::Ellipsoidal)
+///         .unwrap()
+/// };
+/// assert!(airspace.contains(&ellipsoidal(&north)));
+/// assert!(!airspace.contains(&ellipsoidal(&south)));
+/// // 標高のまま判定すると、楕円体高 120m として扱われる
+/// assert!(!airspace.contains(&north));
+///
+/// let ids = airspace.cover(20, Mode::Conservative).unwrap();
+/// let id = |coordinate: &Coordinate| {
+///     coordinate
+///         .to_single_id_with_reference(20, AltitudeReference::Orthometric, &models)
+///         .unwrap()
+/// };
+/// assert!(ids.contains(&id(&north)));
+/// assert!(!ids.contains(&id(&south)));
+///
+/// // ジオイドモデルがなければ地表面の楕円体高を求められない
+/// let terrain_only = AltitudeModels::new(None, Some(&terrain));
+/// assert_eq!(
+///     AboveGround::new(footprint, &terrain_only, 0.0, 50.0).unwrap_err(),
+///     Error::AltitudeModelMissing {
+///         from: AltitudeReference::Agl,
+///         to: AltitudeReference::Ellipsoidal,
+///     }
+/// );
+/// ```
+#[derive(Clone)]
+pub struct AboveGround<'a> {
+    footprint: Polygon,
+    terrain: &'a Terrain,
+    geoid: &'a dyn Geoid,
+    lower: f64,
+    upper: f64,
+}
+
+impl<'a> AboveGround<'a> {
+    /// 底面の頂点・高度の基準の変換モデル・地表面からの高さの範囲（m）から [`AboveGround`] を生成します。
+    ///
+    /// 頂点の高度は使用しません。`lower` と `upper` が逆順の場合は入れ替えます。
+    ///
+    /// # 戻り値
+    /// * `models` にジオイドモデルまたは地表面が与えられていない場合は
+    ///   [`Error::AltitudeModelMissing`] を返します
+    pub fn new(
+        vertices: Vec<Coordinate>,
+        models: &AltitudeModels<'a>,
+        lower: f64,
+        upper: f64,
+    ) -> Result<AboveGround<'a>, Error> {
+        let (Some(geoid), Some(terrain)) = (models.as_geoid(), models.as_terrain()) else {
+            return Err(Error::AltitudeModelMissing {
+                from: AltitudeReference::Agl,
+                to: AltitudeReference::Ellipsoidal,
+            });
+        };
+        Ok(AboveGround {
+            footprint: Polygon::new(vertices, f64::MIN, f64::MAX),
+            terrain,
+            geoid,
+            lower: lower.min(upper),
+            upper: lower.max(upper),
+        })
+    }
+
+    /// 底面の頂点を返します。
+    pub fn as_vertices(&self) -> &[Coordinate] {
+        self.footprint.as_vertices()
+    }
+
+    /// 地表面を返します。
+    pub fn as_terrain(&self) -> &'a Terrain {
+        self.terrain
+    }
+
+    /// ジオイドモデルを返します。
+    pub fn as_geoid(&self) -> &'a dyn Geoid {
+        self.geoid
+    }
+
+    /// 地表面からの高さの下限を返します。
+    pub fn as_lower(&self) -> f64 {
+        self.lower
+    }
+
+    /// 地表面からの高さの上限を返します。
+    pub fn as_upper(&self) -> f64 {
+        self.upper
+    }
+}
+
+impl Shape for AboveGround<'_> {
+    fn relation(&self, id: &SingleId) -> Relation {
+        // 高度の範囲を持たない底面で、水平方向の位置関係を判定する
+        let column = self.footprint.relation(id);
+        if column == Relation::Outside {
+            return Relation::Outside;
+        }
+
+        let z = id.as_z();
+        let (x, y) = (id.as_x() as f64, id.as_y() as f64);
+        let latitude = [helpers::latitude(y + 1.0, z), helpers::latitude(y, z)];
+        let longitude = [helpers::longitude(x, z), helpers::longitude(x + 1.0, z)];
+        let Some(([min, max], complete)) = self.terrain.elevation_bounds(latitude, longitude)
+        else {
+            return Relation::Outside;
+        };
+        // 地表面の標高を楕円体高に直す（ジオイド高の範囲が求まらない場合は内外を決めない）
+        let Ok([undulation_min, undulation_max]) =
+            self.geoid.undulation_bounds(latitude, longitude)
+        else {
+            return Relation::Intersects;
+        };
+        let (min, max) = (min + undulation_min, max + undulation_max);
+
+        let bottom = helpers::altitude(id.as_f() as f64, z);
+        let top = helpers::altitude(id.as_f() as f64 + 1.0, z);
+        if top <= min + self.lower || bottom > max + self.upper {
+            return Relation::Outside;
+        }
+
+        let vertical = max + self.lower <= bottom && top <= min + self.upper;
+        if column == Relation::Inside && complete && vertical {
+            Relation::Inside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        if !self.footprint.contains(point) {
+            return false;
+        }
+        let Ok(undulation) = self
+            .geoid
+            .undulation(point.as_latitude(), point.as_longitude())
+        else {
+            return false;
+        };
+        self.terrain.elevation(point).is_ok_and(|elevation| {
+            let ground = elevation + undulation;
+            (ground + self.lower..=ground + self.upper).contains(&point.as_altitude())
+        })
+    }
+}
+
+impl fmt::Debug for AboveGround<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AboveGround")
+            .field("footprint", &self.footprint)
+            .field("terrain", &self.terrain)
+            .field("geoid", &"..")
+            .field("lower", &self.lower)
+            .field("upper", &self.upper)
+            .finish()
+    }
+}
@@ -0,0 +1,169 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    error::Error,
+    geometry::{
+        helpers::is_folded,
+        shapes::{Mode, Relation, Shape, refine},
+    },
+    spatial_id::{constants::MAX_ZOOM_LEVEL, single::SingleId},
+};
+
+/// 空間 ID の個数の上限を指定して、ズームレベルの混在した被覆を求める。
+///
+/// 最初に `min_z` で図形を被覆し、境界と交差する空間 ID のうち大きいものから順に
+/// [`SingleId::children`] で細分化していく。細分化によって個数が `max_cells` を
+/// 超える場合はその空間 ID を細分化せずに残す。図形に完全に含まれる空間 ID は細分化しない。
+///
+/// `min_z` での被覆がすでに `max_cells` を超える場合は、その被覆をそのまま返す。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{coverer::Coverer, sphere::Sphere};
+/// let center = Coordinate::new(35.0, 139.0, 500.0).unwrap();
+/// let sphere = Sphere::new(center, 1000.0);
+///
+/// let coverer = Coverer::new(10, 24, 64).unwrap();
+/// let cells = coverer.cover(&sphere);
+///
+/// assert!(cells.len() <= 64);
+/// // 球の中心を含む空間 ID が被覆に含まれている
+/// let target = center.to_single_id(24);
+/// assert!(cells.iter().any(|id| target.parent(24 - id.as_z()) == Some(id.clone())));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coverer {
+    min_z: u8,
+    max_z: u8,
+    max_cells: usize,
+}
+
+/// 細分化の候補となる空間 ID と、図形と共有部分を持つ子の一覧
+struct Candidate {
+    id: SingleId,
+    children: Vec<(SingleId, Relation)>,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority().cmp(&other.priority())
+    }
+}
+
+impl Candidate {
+    /// 大きい空間 ID、子の少ない空間 ID ほど優先して細分化する。
+    fn priority(&self) -> (Reverse<u8>, Reverse<usize>, Reverse<&SingleId>) {
+        (
+            Reverse(self.id.as_z()),
+            Reverse(self.children.len()),
+            Reverse(&self.id),
+        )
+    }
+}
+
+impl Coverer {
+    /// ズームレベルの範囲と空間 ID の個数の上限から [`Coverer`] を生成します。
+    ///
+    /// `max_z` が [`MAX_ZOOM_LEVEL`] を超える場合、または `min_z` が `max_z` を超える場合は
+    /// [`Error::ZOutOfRange`] を返します。
+    pub fn new(min_z: u8, max_z: u8, max_cells: usize) -> Result<Coverer, Error> {
+        if max_z > MAX_ZOOM_LEVEL as u8 {
+            return Err(Error::ZOutOfRange { z: max_z });
+        }
+        if min_z > max_z {
+            return Err(Error::ZOutOfRange { z: min_z });
+        }
+        Ok(Coverer {
+            min_z,
+            max_z,
+            max_cells,
+        })
+    }
+
+    /// 最小のズームレベルを返します。
+    pub fn as_min_z(&self) -> u8 {
+        self.min_z
+    }
+
+    /// 最大のズームレベルを返します。
+    pub fn as_max_z(&self) -> u8 {
+        self.max_z
+    }
+
+    /// 空間 ID の個数の上限を返します。
+    pub fn as_max_cells(&self) -> usize {
+        self.max_cells
+    }
+
+    /// 図形を被覆する空間 ID を、昇順に整列して返します。
+    pub fn cover<S: Shape + ?Sized>(&self, shape: &S) -> Vec<SingleId> {
+        let mut result = Vec::new();
+        let mut queue = BinaryHeap::new();
+
+        for id in refine(shape, self.min_z, self.min_z, Mode::Conservative) {
+            let relation = shape.relation(&id);
+            self.push(shape, id, relation, &mut result, &mut queue);
+        }
+
+        while let Some(candidate) = queue.pop() {
+            // 細分化すると 1 つの空間 ID が子の個数分に置き換わる
+            let total = result.len() + queue.len() + candidate.children.len();
+            if total > self.max_cells {
+                result.push(candidate.id);
+                continue;
+            }
+            for (child, relation) in candidate.children {
+                self.push(shape, child, relation, &mut result, &mut queue);
+            }
+        }
+
+        result.sort();
+        result
+    }
+
+    /// 空間 ID を結果に加えるか、細分化の候補として登録する。
+    fn push<S: Shape + ?Sized>(
+        &self,
+        shape: &S,
+        id: SingleId,
+        relation: Relation,
+        result: &mut Vec<SingleId>,
+        queue: &mut BinaryHeap<Candidate>,
+    ) {
+        if relation == Relation::Inside || id.as_z() >= self.max_z {
+            result.push(id);
+            return;
+        }
+
+        let children: Vec<(SingleId, Relation)> = id
+            .children(1)
+            .into_iter()
+            .flatten()
+            .filter(|child| !is_folded(child))
+            .map(|child| {
+                let relation = shape.relation(&child);
+                (child, relation)
+            })
+            .filter(|(_, relation)| *relation != Relation::Outside)
+            .collect();
+
+        // 子がすべて図形の外側であれば、この空間 ID も図形と共有部分を持たない
+        if !children.is_empty() {
+            queue.push(Candidate { id, children });
+        }
+    }
+}
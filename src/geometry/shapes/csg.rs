@@ -0,0 +1,132 @@
+//! 図形の和・積・差による合成
+//!
+//! 合成された図形も [`Shape`] を実装しており、位置関係の判定は空間 ID ごとに
+//! 各図形の [`Shape::relation`] を組み合わせて行います。そのため、各図形の被覆を
+//! 個別に列挙することなく、合成後の図形を直接被覆することができます。
+//!
+//! ```
+//! # use kasane_logic::geometry::coordinate::Coordinate;
+//! # use kasane_logic::geometry::shapes::{Shape, csg::Difference, sphere::Sphere};
+//! let center = Coordinate::new(35.0, 139.0, 500.0).unwrap();
+//!
+//! // 半径 100m の球から半径 60m の球をくり抜いた殻
+//! let shell = Difference::new(Sphere::new(center, 100.0), Sphere::new(center, 60.0));
+//! let cells = shell.cover_adaptive(16, 20).unwrap();
+//!
+//! let inner = center.to_single_id(20);
+//! assert!(!cells.iter().any(|id| inner.parent(20 - id.as_z()) == Some(id.clone())));
+//! ```
+
+use crate::{
+    geometry::{
+        coordinate::Coordinate,
+        shapes::{Relation, Shape},
+    },
+    spatial_id::single::SingleId,
+};
+
+/// 2 つの図形の和
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Union<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Shape, B: Shape> Union<A, B> {
+    /// 2 つの図形の和を生成します。
+    pub fn new(a: A, b: B) -> Union<A, B> {
+        Union { a, b }
+    }
+}
+
+impl<A: Shape, B: Shape> Shape for Union<A, B> {
+    fn relation(&self, id: &SingleId) -> Relation {
+        match self.a.relation(id) {
+            Relation::Inside => Relation::Inside,
+            ra => match (ra, self.b.relation(id)) {
+                (_, Relation::Inside) => Relation::Inside,
+                (Relation::Outside, Relation::Outside) => Relation::Outside,
+                _ => Relation::Intersects,
+            },
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        self.a.contains(point) || self.b.contains(point)
+    }
+}
+
+/// 2 つの図形の積
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{Mode, Shape, csg::Intersection, sphere::Sphere};
+/// let a = Sphere::new(Coordinate::new(35.0, 139.0, 100.0).unwrap(), 50.0);
+/// let b = Sphere::new(Coordinate::new(35.0, 139.0006, 100.0).unwrap(), 50.0);
+/// let lens = Intersection::new(a, b).cover(22, Mode::Conservative).unwrap();
+///
+/// let only_a = a.cover(22, Mode::Conservative).unwrap();
+/// let only_b = b.cover(22, Mode::Conservative).unwrap();
+/// assert!(!lens.is_empty());
+/// assert!(lens.iter().all(|id| only_a.contains(id) && only_b.contains(id)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Shape, B: Shape> Intersection<A, B> {
+    /// 2 つの図形の積を生成します。
+    pub fn new(a: A, b: B) -> Intersection<A, B> {
+        Intersection { a, b }
+    }
+}
+
+impl<A: Shape, B: Shape> Shape for Intersection<A, B> {
+    fn relation(&self, id: &SingleId) -> Relation {
+        match self.a.relation(id) {
+            Relation::Outside => Relation::Outside,
+            ra => match (ra, self.b.relation(id)) {
+                (_, Relation::Outside) => Relation::Outside,
+                (Relation::Inside, Relation::Inside) => Relation::Inside,
+                _ => Relation::Intersects,
+            },
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        self.a.contains(point) && self.b.contains(point)
+    }
+}
+
+/// 図形 `a` から図形 `b` を除いた差
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difference<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Shape, B: Shape> Difference<A, B> {
+    /// 図形 `a` から図形 `b` を除いた差を生成します。
+    pub fn new(a: A, b: B) -> Difference<A, B> {
+        Difference { a, b }
+    }
+}
+
+impl<A: Shape, B: Shape> Shape for Difference<A, B> {
+    fn relation(&self, id: &SingleId) -> Relation {
+        match self.a.relation(id) {
+            Relation::Outside => Relation::Outside,
+            ra => match (ra, self.b.relation(id)) {
+                (_, Relation::Inside) => Relation::Outside,
+                (Relation::Inside, Relation::Outside) => Relation::Inside,
+                _ => Relation::Intersects,
+            },
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        self.a.contains(point) && !self.b.contains(point)
+    }
+}
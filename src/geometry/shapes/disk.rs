@@ -0,0 +1,187 @@
+use crate::{
+    error::Error,
+    geometry::{
+        constants::{WGS84_A, WGS84_B},
+        coordinate::Coordinate,
+        helpers::{cell_bound, ecef_array, norm, sub},
+        shapes::{Mode, Relation, Shape},
+    },
+    spatial_id::{helpers, single::SingleId},
+};
+
+/// 測地線長による判定を行う最小のズームレベル
+///
+/// これより粗いタイルは緯線の膨らみが大きく、頂点までの測地線長では範囲を見積もれない。
+const GEODESIC_TEST_MIN_Z: u8 = 9;
+
+/// 楕円体上の測地線の曲率半径の下限（極での子午線曲率半径 b²/a）
+const MIN_CURVATURE_RADIUS: f64 = WGS84_B * WGS84_B / WGS84_A;
+
+/// 中心点の高度において、中心からの測地線長が `radius`（m）以内の円板を覆う空間 ID を列挙する。
+///
+/// 詳細は [`annulus`] を参照。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::disk::disk;
+/// // 高度 300m の半径 500m の円板
+/// let center = Coordinate::new(35.0, 139.0, 300.0).unwrap();
+/// let ids: Vec<_> = disk(20, &center, 500.0).unwrap().collect();
+///
+/// assert!(ids.contains(&center.to_single_id(20)));
+/// assert!(ids.iter().all(|id| id.as_f() == center.to_single_id(20).as_f()));
+/// ```
+pub fn disk(
+    z: u8,
+    center: &Coordinate,
+    radius: f64,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
+    annulus(z, center, 0.0, radius)
+}
+
+/// 中心点の高度において、中心からの測地線長が `inner` 以上 `outer` 以下（m）の円環を覆う空間 ID を列挙する。
+///
+/// 円は WEBメルカトル平面上ではなく、WGS-84 楕円体上の測地線長で定義される。
+/// 結果は中心点の高度を含む厚さ 1 セル分の層となる。
+/// `inner` と `outer` が逆順の場合は入れ替えて扱う。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::disk::annulus;
+/// // 高度 1000m で、半径 2km から 3km までの範囲
+/// let center = Coordinate::new(35.0, 139.0, 1000.0).unwrap();
+/// let ids: Vec<_> = annulus(18, &center, 2_000.0, 3_000.0).unwrap().collect();
+///
+/// let on_ring = Coordinate::new(35.0, 139.0 + 2_500.0 / 91_290.0, 1000.0).unwrap();
+/// assert!(ids.contains(&on_ring.to_single_id(18)));
+/// assert!(!ids.contains(&center.to_single_id(18)));
+/// ```
+pub fn annulus(
+    z: u8,
+    center: &Coordinate,
+    inner: f64,
+    outer: f64,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
+    for radius in [inner, outer] {
+        if radius.is_nan() || radius < 0.0 {
+            return Err(Error::RadiusOutOfRange { radius });
+        }
+    }
+    Ok(Annulus::new(*center, inner, outer)
+        .cover(z, Mode::Conservative)?
+        .into_iter())
+}
+
+/// 中心点の高度における、測地線長で定義された水平な円環
+///
+/// `inner` を 0 とすると円板になる。体積を持たないため、[`Shape`] としては
+/// [`Mode::Conservative`] でのみ空間 ID を返す。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Annulus {
+    center: Coordinate,
+    inner: f64,
+    outer: f64,
+}
+
+impl Annulus {
+    /// 中心点と内側・外側の半径（m）から [`Annulus`] を生成します。
+    ///
+    /// `inner` と `outer` が逆順の場合は入れ替えます。
+    pub fn new(center: Coordinate, inner: f64, outer: f64) -> Annulus {
+        Annulus {
+            center,
+            inner: inner.min(outer),
+            outer: inner.max(outer),
+        }
+    }
+
+    /// 中心点を返します。
+    pub fn as_center(&self) -> Coordinate {
+        self.center
+    }
+
+    /// 内側の半径を返します。
+    pub fn as_inner(&self) -> f64 {
+        self.inner
+    }
+
+    /// 外側の半径を返します。
+    pub fn as_outer(&self) -> f64 {
+        self.outer
+    }
+
+    /// タイル (X, Y) 上の点までの測地線長の範囲を、弦の長さから見積もる。
+    ///
+    /// 測地線長は弦の長さ以上であり、また曲率半径の下限を持つ円弧の長さ以下である。
+    fn chord_distance_bounds(&self, z: u8, x: u32, y: u32) -> [f64; 2] {
+        // 地表面を含む空間 ID で、タイルの地表部分を内包する
+        let ground = unsafe { SingleId::uncheck_new(z, 0, x, y) };
+        let (bound_center, bound_radius) = cell_bound(&ground);
+
+        let center = unsafe {
+            Coordinate::uncheck_new(self.center.as_latitude(), self.center.as_longitude(), 0.0)
+        };
+        let d = norm(sub(ecef_array(center.into()), bound_center));
+
+        let lower = (d - bound_radius).max(0.0);
+        let chord = d + bound_radius;
+        let upper = if chord < 2.0 * MIN_CURVATURE_RADIUS {
+            2.0 * MIN_CURVATURE_RADIUS * (chord / (2.0 * MIN_CURVATURE_RADIUS)).asin()
+        } else {
+            f64::INFINITY
+        };
+        [lower, upper]
+    }
+
+    /// タイル (X, Y) 上の点までの測地線長の範囲を、タイルの中心と頂点までの測地線長から見積もる。
+    fn geodesic_distance_bounds(&self, z: u8, x: u32, y: u32) -> [f64; 2] {
+        // タイルの北端・南端は Coordinate の緯度の範囲をわずかに超えうるため、検証を行わずに生成する
+        let point = |dx: f64, dy: f64| unsafe {
+            Coordinate::uncheck_new(
+                helpers::latitude(y as f64 + dy, z),
+                helpers::longitude(x as f64 + dx, z),
+                0.0,
+            )
+        };
+
+        let tile = point(0.5, 0.5);
+        let d = self.center.geodesic_distance(&tile);
+        let r = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+            .into_iter()
+            .map(|(dx, dy)| tile.geodesic_distance(&point(dx, dy)))
+            .fold(0.0, f64::max);
+
+        [(d - r).max(0.0), d + r]
+    }
+}
+
+impl Shape for Annulus {
+    fn relation(&self, id: &SingleId) -> Relation {
+        // 空間 ID の高度方向の範囲が、円環の高度を含むか
+        let z = id.as_z();
+        let bottom = helpers::altitude(id.as_f() as f64, z);
+        let top = helpers::altitude(id.as_f() as f64 + 1.0, z);
+        let altitude = self.center.as_altitude();
+        if altitude < bottom || top <= altitude {
+            return Relation::Outside;
+        }
+
+        let [lower, upper] = self.chord_distance_bounds(z, id.as_x(), id.as_y());
+        if lower > self.outer || upper < self.inner {
+            return Relation::Outside;
+        }
+
+        if z >= GEODESIC_TEST_MIN_Z {
+            let [lower, upper] = self.geodesic_distance_bounds(z, id.as_x(), id.as_y());
+            if lower > self.outer || upper < self.inner {
+                return Relation::Outside;
+            }
+        }
+
+        Relation::Intersects
+    }
+
+    fn contains(&self, _point: &Coordinate) -> bool {
+        false
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        helpers::{cell_bound, cell_vertices, ecef_array, norm},
+        shapes::{
+            Mode, Relation, Shape,
+            orientation::{LocalFrame, Orientation, validate_extents},
+        },
+    },
+    spatial_id::single::SingleId,
+};
+
+/// 中心点の局所座標系 ENU で定義された、任意の姿勢の楕円体を覆う空間 ID を列挙する。
+///
+/// `radii` は物体の座標軸（前方・左方・上方）に沿った半径（m）。位置の不確かさを表す
+/// 誤差楕円体などに使用する。空間 ID との位置関係は
+/// [`SpatialId::vertices`](crate::spatial_id::SpatialId::vertices) で得られる 8 頂点から保守的に判定する。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{ellipsoid::ellipsoid, orientation::Orientation};
+/// // 東西に長い誤差楕円体
+/// let center = Coordinate::new(35.0, 139.0, 100.0).unwrap();
+/// let orientation = Orientation::new(90.0, 0.0, 0.0);
+/// let ids: Vec<_> = ellipsoid(22, &center, [60.0, 15.0, 10.0], orientation).unwrap().collect();
+///
+/// let east = Coordinate::new(35.0, 139.0 + 50.0 / 91_290.0, 100.0).unwrap();
+/// let north = Coordinate::new(35.0 + 50.0 / 110_950.0, 139.0, 100.0).unwrap();
+/// assert!(ids.contains(&east.to_single_id(22)));
+/// assert!(!ids.contains(&north.to_single_id(22)));
+/// ```
+///
+/// 半径が 0 以下の軸を持つ楕円体の検知:
+/// ```
+/// # use kasane_logic::error::Error;
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{ellipsoid::ellipsoid, orientation::Orientation};
+/// let center = Coordinate::new(35.0, 139.0, 100.0).unwrap();
+/// let result = ellipsoid(22, &center, [0.0, 10.0, 10.0], Orientation::default());
+/// assert!(matches!(result, Err(Error::ExtentOutOfRange { extent: 0.0 })));
+/// ```
+pub fn ellipsoid(
+    z: u8,
+    center: &Coordinate,
+    radii: [f64; 3],
+    orientation: Orientation,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
+    Ok(OrientedEllipsoid::new(*center, radii, orientation)?
+        .cover(z, Mode::Conservative)?
+        .into_iter())
+}
+
+/// 中心点・半径（m）・姿勢で定義された楕円体
+#[derive(Debug, Clone, Copy)]
+pub struct OrientedEllipsoid {
+    center: Coordinate,
+    radii: [f64; 3],
+    orientation: Orientation,
+    frame: LocalFrame,
+}
+
+impl OrientedEllipsoid {
+    /// 中心点・物体の座標軸（前方・左方・上方）に沿った半径（m）・姿勢から [`OrientedEllipsoid`] を生成します。
+    ///
+    /// # 戻り値
+    /// * 半径のいずれかが正の値でない場合は [`Error::ExtentOutOfRange`] を返します
+    pub fn new(
+        center: Coordinate,
+        radii: [f64; 3],
+        orientation: Orientation,
+    ) -> Result<OrientedEllipsoid, Error> {
+        validate_extents(radii)?;
+        Ok(OrientedEllipsoid {
+            center,
+            radii,
+            orientation,
+            frame: LocalFrame::new(center, orientation),
+        })
+    }
+
+    /// 中心点を返します。
+    pub fn as_center(&self) -> Coordinate {
+        self.center
+    }
+
+    /// 半径を返します。
+    pub fn as_radii(&self) -> [f64; 3] {
+        self.radii
+    }
+
+    /// 姿勢を返します。
+    pub fn as_orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// 物体の座標系の点を、楕円体が単位球となるように正規化する。
+    fn normalize(&self, p: [f64; 3]) -> [f64; 3] {
+        std::array::from_fn(|i| p[i] / self.radii[i])
+    }
+}
+
+impl Shape for OrientedEllipsoid {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let min_radius = self.radii.iter().copied().fold(f64::INFINITY, f64::min);
+
+        // 正規化した距離 k の点から楕円体までの距離は、最小半径 × (k - 1) 以上
+        let (center, radius) = cell_bound(id);
+        let k = norm(self.normalize(self.frame.local(center)));
+        if min_radius * (k - 1.0) > radius {
+            return Relation::Outside;
+        }
+
+        // 楕円体は凸であるため、8 頂点が弧の膨らみの分だけ内側にあれば空間 ID 全体が含まれる
+        let (vertices, margin) = cell_vertices(id);
+        let limit = 1.0 - margin / min_radius;
+        if vertices
+            .iter()
+            .all(|&v| norm(self.normalize(self.frame.local(v))) <= limit)
+        {
+            Relation::Inside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        let p = self.frame.local(ecef_array((*point).into()));
+        norm(self.normalize(p)) <= 1.0
+    }
+}
@@ -0,0 +1,308 @@
+//! 傾いた平面で区切られた半空間
+//!
+//! 空港の制限表面（進入表面・転移表面・水平表面）のように、多角形で切り取られた
+//! 傾斜面の上側または下側を空間 ID で表すために使用します。
+//!
+//! ```
+//! # use kasane_logic::geometry::coordinate::Coordinate;
+//! # use kasane_logic::geometry::shapes::half_space::{half_space, Plane, Side};
+//! # use kasane_logic::geometry::shapes::polygon::Polygon;
+//! // 滑走路端から北へ 1/50 の勾配で上昇する進入表面
+//! let threshold = Coordinate::new(35.0, 139.0, 10.0).unwrap();
+//! let surface = Plane::from_slope(threshold, 0.02, 0.0).unwrap();
+//!
+//! let footprint = Polygon::new(
+//!     vec![
+//!         Coordinate::new(35.0, 138.999, 0.0).unwrap(),
+//!         Coordinate::new(35.0, 139.001, 0.0).unwrap(),
+//!         Coordinate::new(35.02, 139.003, 0.0).unwrap(),
+//!         Coordinate::new(35.02, 138.997, 0.0).unwrap(),
+//!     ],
+//!     0.0,
+//!     100.0,
+//! );
+//!
+//! let above = half_space(20, surface, Side::Above, &footprint).unwrap();
+//!
+//! // 滑走路端付近では高度約 10m より上、約 2km 先では約 50m より上が含まれる
+//! let near = Coordinate::new(35.0005, 139.0, 40.0).unwrap().to_single_id(20);
+//! let far_low = Coordinate::new(35.018, 139.0, 30.0).unwrap().to_single_id(20);
+//! let far_high = Coordinate::new(35.018, 139.0, 60.0).unwrap().to_single_id(20);
+//! let covered = |id: kasane_logic::spatial_id::single::SingleId| {
+//!     above.iter().any(|r| r.to_single().any(|s| s == id))
+//! };
+//! assert!(covered(near));
+//! assert!(!covered(far_low));
+//! assert!(covered(far_high));
+//! ```
+
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        enu::{Enu, EnuFrame},
+        helpers::{cell_bound, dot, ecef_array, lowest_unfolded_f, norm, sub},
+        shapes::{Mode, Relation, Shape, accepts, relation_from_distance},
+    },
+    spatial_id::{
+        constants::{F_MAX, MAX_ZOOM_LEVEL},
+        range::RangeId,
+        single::SingleId,
+    },
+};
+
+/// 基準点を通る局所座標系 ENU 上の平面
+///
+/// 法線の向きを平面の上側とする。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    origin: Coordinate,
+    /// ENU（東・北・上）で表した単位法線ベクトル
+    normal: [f64; 3],
+    /// 基準点の ECEF 座標
+    ecef_origin: [f64; 3],
+    /// ECEF で表した単位法線ベクトル
+    ecef_normal: [f64; 3],
+}
+
+impl Plane {
+    /// 基準点と、基準点における ENU（東・北・上）で表した法線ベクトルから [`Plane`] を生成します。
+    ///
+    /// 法線ベクトルは正規化されます。
+    ///
+    /// # 戻り値
+    /// * 法線ベクトルの長さが 0、または成分が有限の数値でない場合は [`Error::NormalOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::half_space::Plane;
+    /// let origin = Coordinate::new(35.0, 139.0, 10.0).unwrap();
+    /// let plane = Plane::new(origin, [0.0, 0.0, 2.0]).unwrap();
+    /// assert_eq!(plane.as_normal(), [0.0, 0.0, 1.0]);
+    ///
+    /// assert_eq!(
+    ///     Plane::new(origin, [0.0, 0.0, 0.0]),
+    ///     Err(Error::NormalOutOfRange { normal: [0.0, 0.0, 0.0] })
+    /// );
+    /// assert!(Plane::new(origin, [f64::NAN, 0.0, 1.0]).is_err());
+    /// ```
+    pub fn new(origin: Coordinate, normal: [f64; 3]) -> Result<Plane, Error> {
+        let length = norm(normal);
+        if !length.is_finite() || length == 0.0 {
+            return Err(Error::NormalOutOfRange { normal });
+        }
+        Ok(Plane::from_unit_normal(origin, normal.map(|v| v / length)))
+    }
+
+    /// 基準点と ENU で表した単位法線ベクトルから生成する。
+    fn from_unit_normal(origin: Coordinate, normal: [f64; 3]) -> Plane {
+        let frame = EnuFrame::new(origin);
+        let [east, north, up] = normal;
+        Plane {
+            origin,
+            normal,
+            ecef_origin: ecef_array(origin.into()),
+            ecef_normal: ecef_array(frame.rotate_to_ecef(&Enu::new(east, north, up))),
+        }
+    }
+
+    /// 基準点を通る水平な平面を生成します。
+    pub fn horizontal(origin: Coordinate) -> Plane {
+        Plane::from_unit_normal(origin, [0.0, 0.0, 1.0])
+    }
+
+    /// 基準点と勾配、上り方向の方位角から [`Plane`] を生成します。
+    ///
+    /// `slope` は水平距離に対する高さの比（1/50 の勾配なら `0.02`）、
+    /// `azimuth` は平面が上る方向の方位角（度、北から時計回り）です。
+    ///
+    /// # 戻り値
+    /// * `slope` または `azimuth` が有限の数値でない場合は [`Error::NormalOutOfRange`] を返します
+    pub fn from_slope(origin: Coordinate, slope: f64, azimuth: f64) -> Result<Plane, Error> {
+        let (sin, cos) = azimuth.to_radians().sin_cos();
+        Plane::new(origin, [-slope * sin, -slope * cos, 1.0])
+    }
+
+    /// 基準点を返します。
+    pub fn as_origin(&self) -> Coordinate {
+        self.origin
+    }
+
+    /// ENU で表した単位法線ベクトルを返します。
+    pub fn as_normal(&self) -> [f64; 3] {
+        self.normal
+    }
+
+    /// ECEF 上の点から平面までの符号付き距離（上側で正）を返す。
+    fn signed_distance(&self, p: [f64; 3]) -> f64 {
+        dot(sub(p, self.ecef_origin), self.ecef_normal)
+    }
+}
+
+/// 半空間が平面のどちら側か
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// 平面より下側（法線と逆側）
+    Below,
+    /// 平面より上側（法線の側）
+    Above,
+}
+
+/// 平面で区切られた半空間
+///
+/// 範囲が無限であるため、被覆を求める際は [`half_space`] のように範囲を持つ図形で切り取って使用する。
+/// [`Shape::cover_ranges`] は列ごとに F の範囲を直接求めるが、全球の列を列挙する。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HalfSpace {
+    plane: Plane,
+    side: Side,
+}
+
+impl HalfSpace {
+    /// 平面とその側から [`HalfSpace`] を生成します。
+    pub fn new(plane: Plane, side: Side) -> HalfSpace {
+        HalfSpace { plane, side }
+    }
+
+    /// 境界の平面を返します。
+    pub fn as_plane(&self) -> Plane {
+        self.plane
+    }
+
+    /// 半空間が平面のどちら側かを返します。
+    pub fn as_side(&self) -> Side {
+        self.side
+    }
+
+    /// 半空間の内部で負となる符号付き距離を返す。
+    fn distance(&self, p: [f64; 3]) -> f64 {
+        match self.side {
+            Side::Below => self.plane.signed_distance(p),
+            Side::Above => -self.plane.signed_distance(p),
+        }
+    }
+
+    /// 列 (X, Y) のうち、`mode` での被覆に含まれる空間 ID の F の範囲を返す。
+    ///
+    /// 列に沿って平面からの距離は単調に変化するため、含まれる範囲は列の上端または下端に接する。
+    /// 境界の F は二分探索で求め、`refine` と同じく折り返した高度の空間 ID は除く。
+    fn column_range(&self, z: u8, x: u32, y: u32, mode: Mode) -> Option<[i32; 2]> {
+        let lowest = lowest_unfolded_f(z);
+        let highest = F_MAX[z as usize];
+        let accept = |f: i32| accepts(self, &unsafe { SingleId::uncheck_new(z, f, x, y) }, mode);
+
+        match (accept(lowest), accept(highest)) {
+            (true, true) => Some([lowest, highest]),
+            (false, false) => None,
+            (true, false) => Some([lowest, partition(lowest, highest, accept) - 1]),
+            (false, true) => Some([partition(lowest, highest, |f| !accept(f)), highest]),
+        }
+    }
+}
+
+/// `predicate` が `start` で真、`end` で偽となるとき、`start..=end` のうち偽となる最小の値を返す。
+fn partition(mut start: i32, mut end: i32, predicate: impl Fn(i32) -> bool) -> i32 {
+    while end - start > 1 {
+        let middle = start + (end - start) / 2;
+        if predicate(middle) {
+            start = middle;
+        } else {
+            end = middle;
+        }
+    }
+    end
+}
+
+impl Shape for HalfSpace {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let (center, radius) = cell_bound(id);
+        relation_from_distance(self.distance(center), radius)
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        self.distance(ecef_array((*point).into())) <= 0.0
+    }
+
+    fn cover_ranges(&self, z: u8, mode: Mode) -> Result<Vec<RangeId>, Error> {
+        if z > MAX_ZOOM_LEVEL as u8 {
+            return Err(Error::ZOutOfRange { z });
+        }
+        let n = 1_u32 << z;
+        let mut ranges = Vec::new();
+        for x in 0..n {
+            for y in 0..n {
+                if let Some(f) = self.column_range(z, x, y, mode) {
+                    ranges.push(unsafe { RangeId::uncheck_new(z, f, [x, x], [y, y]) });
+                }
+            }
+        }
+        Ok(ranges)
+    }
+}
+
+/// 平面の上側または下側のうち、`clip` の内部にある部分を覆う空間 ID を、
+/// (X, Y) の列ごとの [`RangeId`] として返す。
+///
+/// `clip` には多角形の底面を持つ [`Polygon`](crate::geometry::shapes::polygon::Polygon) や
+/// [`RangeId`] など、範囲を持つ任意の図形を指定できる。
+/// 平面と交差する空間 ID も含まれる。
+///
+/// 結果は [`HalfSpace`] と `clip` の [`Intersection`](crate::geometry::shapes::csg::Intersection)
+/// を [`Mode::Conservative`] で被覆したものと同じである。`clip` の列ごとに平面と交わる F を
+/// 二分探索で求めるため、[`Polygon`](crate::geometry::shapes::polygon::Polygon) のように
+/// 列ごとの範囲を直接求める図形で切り取れば、地中深くまで続く範囲も空間 ID を列挙せずに求まる。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::half_space::{half_space, Plane, Side};
+/// # use kasane_logic::geometry::shapes::polygon::Polygon;
+/// // 約 50m 四方の範囲について、水平表面（高度 45m）より下側をすべて求める
+/// let surface = Plane::horizontal(Coordinate::new(35.0, 139.0, 45.0).unwrap());
+/// let footprint = Polygon::new(
+///     vec![
+///         Coordinate::new(35.0, 139.0, 0.0).unwrap(),
+///         Coordinate::new(35.0, 139.00055, 0.0).unwrap(),
+///         Coordinate::new(35.00045, 139.00055, 0.0).unwrap(),
+///         Coordinate::new(35.00045, 139.0, 0.0).unwrap(),
+///     ],
+///     f64::MIN,
+///     f64::MAX,
+/// );
+///
+/// let below = half_space(24, surface, Side::Below, &footprint).unwrap();
+///
+/// // 列ごとに 1 つの RangeId となり、上端は高度 45m 付近（z=24 の空間 ID の高さは 2m）
+/// let top = Coordinate::new(35.0002, 139.0003, 45.0).unwrap().to_single_id(24);
+/// let column = below
+///     .iter()
+///     .find(|r| r.as_x() == [top.as_x(); 2] && r.as_y() == [top.as_y(); 2])
+///     .unwrap();
+/// assert!((column.as_f()[1] - top.as_f()).abs() <= 1);
+/// assert!(column.as_f()[0] < -3_000_000);
+/// ```
+pub fn half_space<C: Shape>(
+    z: u8,
+    plane: Plane,
+    side: Side,
+    clip: C,
+) -> Result<Vec<RangeId>, Error> {
+    let half = HalfSpace::new(plane, side);
+    let mut ranges = Vec::new();
+    for range in clip.cover_ranges(z, Mode::Conservative)? {
+        let ([f_min, f_max], [x_min, x_max], [y_min, y_max]) =
+            (range.as_f(), range.as_x(), range.as_y());
+        for x in x_min..=x_max {
+            for y in y_min..=y_max {
+                let Some([lower, upper]) = half.column_range(z, x, y, Mode::Conservative) else {
+                    continue;
+                };
+                let f = [f_min.max(lower), f_max.min(upper)];
+                if f[0] <= f[1] {
+                    ranges.push(unsafe { RangeId::uncheck_new(z, f, [x, x], [y, y]) });
+                }
+            }
+        }
+    }
+    Ok(ranges)
+}
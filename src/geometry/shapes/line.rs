@@ -2,73 +2,339 @@ use std::f64;
 
 use crate::{
     error::Error,
-    geometry::{coordinate::Coordinate, ecef::Ecef},
-    spatial_id::{constants::MAX_ZOOM_LEVEL, single::SingleId},
+    geometry::{
+        coordinate::Coordinate,
+        ecef::Ecef,
+        ellipsoid::Ellipsoid,
+        helpers::{
+            cell_bound, distance_to_segment, ecef_array, geodetic_to_matrix, wrap_longitude,
+        },
+        shapes::{Mode, Relation, Shape},
+    },
+    spatial_id::{
+        constants::{F_MAX, F_MIN, MAX_ZOOM_LEVEL},
+        single::SingleId,
+    },
 };
 
+/// 経路をインデックス空間上の直線で近似する際の許容誤差（セル単位）
+pub(crate) const TRACE_TOLERANCE: f64 = 1e-3;
+
+/// 経路の分割を打ち切る再帰の深さ
+const MAX_TRACE_DEPTH: u32 = 24;
+
+/// 経路を ECEF 上の折れ線で近似する際の、各座標軸方向の許容誤差（m）
+const POLYLINE_TOLERANCE: f64 = 1.0;
+
+/// 2 点間を結ぶ経路の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineMode {
+    /// ECEF 上の直線（弦）。長い区間では地中を通過する。
+    #[default]
+    Chord,
+    /// WGS-84 楕円体上の測地線（大円航路に相当する最短経路）。高度は始点から終点へ線形に補間する。
+    GreatCircle,
+    /// 航程線（メルカトル平面上の直線）。高度は始点から終点へ線形に補間する。
+    Rhumb,
+}
+
 /// 指定された 2 点で構成される直線を覆う空間 ID を列挙する。
+///
+/// 経路は ECEF 上の直線（[`LineMode::Chord`]）として扱う。
 pub fn line(z: u8, a: Coordinate, b: Coordinate) -> Result<impl Iterator<Item = SingleId>, Error> {
+    line_with_mode(z, a, b, LineMode::Chord)
+}
+
+/// 指定された 2 点を、[`LineMode`] で選択した経路で結んだ線を覆う空間 ID を列挙する。
+///
+/// 経路はインデックス空間上で直線と見なせるまで適応的に分割され、各区間を順に走査する。
+/// 区間の継ぎ目で重複や欠落は生じず、返される空間 ID は始点側から順に隣接している。
+/// 経度 ±180° をまたぐ経路は短い方向に進み、WEBメルカトルの緯度範囲外を通る部分は除外される。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::line::{line_with_mode, LineMode};
+/// let tokyo = Coordinate::new(35.68, 139.77, 10_000.0).unwrap();
+/// let sf = Coordinate::new(37.62, -122.38, 10_000.0).unwrap();
+///
+/// let route: Vec<_> = line_with_mode(12, tokyo, sf, LineMode::GreatCircle).unwrap().collect();
+/// assert_eq!(route.first(), Some(&tokyo.to_single_id(12)));
+/// assert_eq!(route.last(), Some(&sf.to_single_id(12)));
+///
+/// // 大円航路は一定の高度を保つ
+/// let f = tokyo.to_single_id(12).as_f();
+/// assert!(route.iter().all(|id| id.as_f() == f));
+///
+/// // 連続する空間 ID は常に隣接している（経度方向は循環）
+/// let n = 1_i64 << 12;
+/// for w in route.windows(2) {
+///     let dx = (w[0].as_x() as i64 - w[1].as_x() as i64).rem_euclid(n);
+///     let dy = (w[0].as_y() as i64 - w[1].as_y() as i64).abs();
+///     assert_eq!(dx.min(n - dx) + dy, 1);
+/// }
+///
+/// // 弦は地中を通過する
+/// let chord: Vec<_> = line_with_mode(12, tokyo, sf, LineMode::Chord).unwrap().collect();
+/// assert!(chord.iter().any(|id| id.as_f() < 0));
+/// ```
+pub fn line_with_mode(
+    z: u8,
+    a: Coordinate,
+    b: Coordinate,
+    mode: LineMode,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
     if z > MAX_ZOOM_LEVEL as u8 {
         return Err(Error::ZOutOfRange { z });
     }
-    let ecef_a: Ecef = a.into();
-    let ecef_b: Ecef = b.into();
-    let dx = ecef_a.as_x() - ecef_b.as_x();
-    let dy = ecef_a.as_y() - ecef_b.as_y();
-    let dz = ecef_a.as_z() - ecef_b.as_z();
-    let distance = (dx * dx + dy * dy + dz * dz).sqrt();
-    let (v1, v2) = (a.to_single_id(z), b.to_single_id(z));
-    let diff = ((v1.as_f() - v2.as_f()).abs()
-        + (v1.as_x() as i32 - v2.as_x() as i32).abs()
-        + (v1.as_y() as i32 - v2.as_y() as i32).abs()) as f64;
-    let devide_num = 5 + (diff / 120.0 + distance / 2000.0).floor() as u16;
-    let mut coordinates = Vec::new();
-    for i in 0..=devide_num {
-        let t = i as f64 / devide_num as f64;
-        let x = ecef_a.as_x() * (1.0 - t) + ecef_b.as_x() * t;
-        let y = ecef_a.as_y() * (1.0 - t) + ecef_b.as_y() * t;
-        let z_pos = ecef_a.as_z() * (1.0 - t) + ecef_b.as_z() * t;
-        let coo: Coordinate = Ecef::new(x, y, z_pos).try_into()?;
-        coordinates.push(coo);
+    Ok(Line::new(a, b, mode).trace(z).into_iter())
+}
+
+/// 2 点と経路の種類で定義される線
+///
+/// 体積を持たないため、[`Shape`] としては [`Mode::Conservative`] でのみ空間 ID を返す。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{Mode, Relation, Shape};
+/// # use kasane_logic::geometry::shapes::line::{line, line_with_mode, Line, LineMode};
+/// let a = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+/// let b = Coordinate::new(35.001, 139.002, 50.0).unwrap();
+/// let shape = Line::new(a, b, LineMode::Chord);
+///
+/// let mut traced: Vec<_> = line(20, a, b).unwrap().collect();
+/// traced.sort();
+/// assert_eq!(shape.cover(20, Mode::Conservative).unwrap(), traced);
+/// assert!(shape.cover(20, Mode::Strict).unwrap().is_empty());
+///
+/// // 長い測地線でも、経路が通過する空間 ID を外側と判定しない
+/// let tokyo = Coordinate::new(35.68, 139.77, 10_000.0).unwrap();
+/// let sf = Coordinate::new(37.62, -122.38, 10_000.0).unwrap();
+/// let route = Line::new(tokyo, sf, LineMode::GreatCircle);
+/// let traced: Vec<_> = line_with_mode(12, tokyo, sf, LineMode::GreatCircle).unwrap().collect();
+/// assert!(traced.iter().all(|id| route.relation(id) != Relation::Outside));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Line {
+    a: Coordinate,
+    b: Coordinate,
+    mode: LineMode,
+    /// 経路を近似する ECEF 上の折れ線
+    polyline: Vec<[f64; 3]>,
+}
+
+impl Line {
+    /// 始点・終点と経路の種類から [`Line`] を生成します。
+    pub fn new(a: Coordinate, b: Coordinate, mode: LineMode) -> Line {
+        let path = geodetic_path(a, b, mode);
+        let to_ecef = |t: f64| ecef_array(Ecef::from_geodetic_unchecked(path(t)));
+        let polyline = flatten_path(&to_ecef, POLYLINE_TOLERANCE);
+
+        Line {
+            a,
+            b,
+            mode,
+            polyline,
+        }
     }
-    let mut voxels: Vec<SingleId> = Vec::new();
-    for pair in coordinates.windows(2) {
-        let start = pair[0];
-        let end = pair[1];
-        let line_iter = line_dda(z, start, end)?;
-        voxels.pop();
-        voxels.extend(line_iter);
+
+    /// 始点を返します。
+    pub fn as_start(&self) -> Coordinate {
+        self.a
     }
-    Ok(voxels.into_iter())
-}
 
-fn coordinate_to_matrix(p: Coordinate, z: u8) -> [f64; 3] {
-    let lat = p.as_latitude();
-    let lon = p.as_longitude();
-    let alt = p.as_altitude();
+    /// 終点を返します。
+    pub fn as_end(&self) -> Coordinate {
+        self.b
+    }
 
-    // 空間idの高さはz=25でちょうど1mになるように定義されている
-    let factor = 2_f64.powi(z as i32 - 25);
-    let f = factor * alt;
+    /// 経路の種類を返します。
+    pub fn as_mode(&self) -> LineMode {
+        self.mode
+    }
 
-    let n = 2u64.pow(z as u32) as f64;
-    let x = (lon + 180.0) / 360.0 * n;
+    /// 経路を始点側から順に走査し、通過する空間 ID を列挙する。
+    fn trace(&self, z: u8) -> Vec<SingleId> {
+        let path = geodetic_path(self.a, self.b, self.mode);
+        trace_path(z, |t| {
+            let [lat, lon, alt] = path(t);
+            geodetic_to_matrix(lat, lon, alt, z)
+        })
+    }
 
-    let lat_rad = lat.to_radians();
-    let y = (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * n;
-    [f, x, y]
+    /// ECEF 上の点から経路を近似する折れ線までの距離を返す。
+    fn distance_from(&self, p: [f64; 3]) -> f64 {
+        self.polyline
+            .windows(2)
+            .map(|w| distance_to_segment(p, w[0], w[1]))
+            .fold(f64::INFINITY, f64::min)
+    }
 }
 
-pub(crate) fn line_dda(
-    z: u8,
+impl Shape for Line {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let (center, radius) = cell_bound(id);
+        // 折れ線と経路のずれは各座標軸で許容誤差以下であるため、距離では √3 倍以下となる
+        if self.distance_from(center) > radius + POLYLINE_TOLERANCE * 2.0 {
+            Relation::Outside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, _point: &Coordinate) -> bool {
+        false
+    }
+
+    fn cover(&self, z: u8, mode: Mode) -> Result<Vec<SingleId>, Error> {
+        if z > MAX_ZOOM_LEVEL as u8 {
+            return Err(Error::ZOutOfRange { z });
+        }
+        match mode {
+            Mode::Conservative => {
+                let mut voxels = self.trace(z);
+                voxels.sort();
+                voxels.dedup();
+                Ok(voxels)
+            }
+            Mode::Strict | Mode::CenterIn => Ok(Vec::new()),
+        }
+    }
+}
+
+/// 媒介変数 `t ∈ [0, 1]` から経路上の点 `[lat, lon, alt]` を返す関数を生成する。
+///
+/// 経度は始点からの連続値として扱うため、`-180.0..=180.0` を超える場合がある。
+pub(crate) fn geodetic_path(
     a: Coordinate,
     b: Coordinate,
-) -> Result<impl Iterator<Item = SingleId>, Error> {
-    if z > MAX_ZOOM_LEVEL as u8 {
-        return Err(Error::ZOutOfRange { z });
+    mode: LineMode,
+) -> Box<dyn Fn(f64) -> [f64; 3]> {
+    let lon_a = a.as_longitude();
+    let unwrap = move |lon: f64| lon_a + wrap_longitude(lon - lon_a);
+    let (alt_a, alt_b) = (a.as_altitude(), b.as_altitude());
+
+    match mode {
+        LineMode::Chord => {
+            let ecef_a: Ecef = a.into();
+            let ecef_b: Ecef = b.into();
+            Box::new(move |t| {
+                let [lat, lon, h] = Ecef::new(
+                    ecef_a.as_x() * (1.0 - t) + ecef_b.as_x() * t,
+                    ecef_a.as_y() * (1.0 - t) + ecef_b.as_y() * t,
+                    ecef_a.as_z() * (1.0 - t) + ecef_b.as_z() * t,
+                )
+                .to_geodetic_unchecked();
+                [lat, unwrap(lon), h]
+            })
+        }
+        LineMode::GreatCircle => {
+            // 測地線の逆問題で方位角と長さを求め、順問題で途中の点を求める
+            let geodesic = Ellipsoid::WGS84.geodesic();
+            let lat_a = a.as_latitude();
+            let inverse = geodesic.inverse(lat_a, lon_a, b.as_latitude(), b.as_longitude());
+            let (azimuth, distance) = (inverse.as_azimuth1(), inverse.as_distance());
+
+            Box::new(move |t| {
+                let direct = geodesic.direct(lat_a, lon_a, azimuth, distance * t);
+                [
+                    direct.as_latitude(),
+                    unwrap(direct.as_longitude()),
+                    alt_a * (1.0 - t) + alt_b * t,
+                ]
+            })
+        }
+        LineMode::Rhumb => {
+            // メルカトル座標（等長緯度）上で線形に補間する
+            let psi_a = a.as_latitude().to_radians().tan().asinh();
+            let psi_b = b.as_latitude().to_radians().tan().asinh();
+            let lon_b = unwrap(b.as_longitude());
+            Box::new(move |t| {
+                let psi = psi_a * (1.0 - t) + psi_b * t;
+                [
+                    psi.sinh().atan().to_degrees(),
+                    lon_a * (1.0 - t) + lon_b * t,
+                    alt_a * (1.0 - t) + alt_b * t,
+                ]
+            })
+        }
+    }
+}
+
+fn lerp(p0: [f64; 3], p1: [f64; 3], t: f64) -> [f64; 3] {
+    [
+        p0[0] * (1.0 - t) + p1[0] * t,
+        p0[1] * (1.0 - t) + p1[1] * t,
+        p0[2] * (1.0 - t) + p1[2] * t,
+    ]
+}
+
+/// 媒介変数 `t ∈ [0, 1]` からインデックス空間 `[f, x, y]` への写像で表された経路を覆う空間 ID を、
+/// 始点側から順に列挙する。
+///
+/// 経路はインデックス空間上の直線からのずれが [`TRACE_TOLERANCE`] 以下になるまで二分され、
+/// 各区間は [`line_dda`] で走査される。
+pub(crate) fn trace_path(z: u8, path: impl Fn(f64) -> [f64; 3]) -> Vec<SingleId> {
+    let points = flatten_path(&path, TRACE_TOLERANCE);
+
+    let mut voxels: Vec<SingleId> = Vec::new();
+    for pair in points.windows(2) {
+        for index in line_dda(pair[0], pair[1]) {
+            if let Some(id) = index_to_single_id(z, index) {
+                // 区間の継ぎ目では同じセルが続けて現れる
+                if voxels.last() != Some(&id) {
+                    voxels.push(id);
+                }
+            }
+        }
     }
-    let origin1 = coordinate_to_matrix(a, z);
-    let origin2 = coordinate_to_matrix(b, z);
+    voxels
+}
+
+/// 媒介変数 `t ∈ [0, 1]` で表された経路を、各区間の直線からのずれが `tolerance` 以下になるまで
+/// 二分し、区間の端点を始点側から順に返す。
+pub(crate) fn flatten_path(path: &impl Fn(f64) -> [f64; 3], tolerance: f64) -> Vec<[f64; 3]> {
+    let start = path(0.0);
+    let end = path(1.0);
+    let mut points = vec![start];
+    subdivide(path, (0.0, start), (1.0, end), tolerance, 0, &mut points);
+    points
+}
+
+fn subdivide(
+    path: &impl Fn(f64) -> [f64; 3],
+    (t0, p0): (f64, [f64; 3]),
+    (t1, p1): (f64, [f64; 3]),
+    tolerance: f64,
+    depth: u32,
+    out: &mut Vec<[f64; 3]>,
+) {
+    let flat = [0.25, 0.5, 0.75].iter().all(|&s| {
+        let p = path(t0 + (t1 - t0) * s);
+        let q = lerp(p0, p1, s);
+        let deviation = (p[0] - q[0])
+            .abs()
+            .max((p[1] - q[1]).abs())
+            .max((p[2] - q[2]).abs());
+        // 極など写像が発散する点ではそれ以上分割しない
+        !deviation.is_finite() || deviation <= tolerance
+    });
+
+    if flat || depth >= MAX_TRACE_DEPTH {
+        out.push(p1);
+        return;
+    }
+
+    let tm = (t0 + t1) / 2.0;
+    let pm = path(tm);
+    subdivide(path, (t0, p0), (tm, pm), tolerance, depth + 1, out);
+    subdivide(path, (tm, pm), (t1, p1), tolerance, depth + 1, out);
+}
+
+/// インデックス空間上の 2 点を結ぶ線分が通過するセルを、始点側から順に `[f, x, y]` で列挙する。
+///
+/// X は経度方向に連続的に延長された値のまま返すため、呼び出し側で [`index_to_single_id`] により
+/// 正規化する必要がある。
+pub(crate) fn line_dda(origin1: [f64; 3], origin2: [f64; 3]) -> impl Iterator<Item = [i64; 3]> {
     let offsets = origin1.map(|x| x.floor());
     let vp1 = [
         origin1[0] - offsets[0],
@@ -85,7 +351,7 @@ pub(crate) fn line_dda(
         (vp2[1] - vp1[1]).abs(),
         (vp2[2] - vp1[2]).abs(),
     ];
-    let offsets_int = offsets.map(|x| x as i32);
+    let offsets_int = offsets.map(|x| x as i64);
     let max_d = d_total[0].max(d_total[1]).max(d_total[2]);
     let max_flag: usize = if max_d == d_total[0] {
         0
@@ -96,12 +362,12 @@ pub(crate) fn line_dda(
     };
     let other_flag_1 = (max_flag + 1) % 3;
     let other_flag_2 = (max_flag + 2) % 3;
-    let i1 = vp1[max_flag].floor() as i32;
-    let j1 = vp1[other_flag_1].floor() as i32;
-    let k1 = vp1[other_flag_2].floor() as i32;
-    let i2 = vp2[max_flag].floor() as i32;
-    let j2 = vp2[other_flag_1].floor() as i32;
-    let k2 = vp2[other_flag_2].floor() as i32;
+    let i1 = vp1[max_flag].floor() as i64;
+    let j1 = vp1[other_flag_1].floor() as i64;
+    let k1 = vp1[other_flag_2].floor() as i64;
+    let i2 = vp2[max_flag].floor() as i64;
+    let j2 = vp2[other_flag_1].floor() as i64;
+    let k2 = vp2[other_flag_2].floor() as i64;
     let d_o1 = if vp2[other_flag_1] != vp1[other_flag_1] {
         d_total[max_flag] / d_total[other_flag_1]
     } else {
@@ -140,19 +406,16 @@ pub(crate) fn line_dda(
         (3 - other_flag_1) % 3,
     ];
     let mut current = [i1, j1, k1];
-    let sign_i = (vp2[max_flag] - vp1[max_flag]).signum() as i32;
-    let sign_j = (vp2[other_flag_1] - vp1[other_flag_1]).signum() as i32;
-    let sign_k = (vp2[other_flag_2] - vp1[other_flag_2]).signum() as i32;
+    let sign_i = (vp2[max_flag] - vp1[max_flag]).signum() as i64;
+    let sign_j = (vp2[other_flag_1] - vp1[other_flag_1]).signum() as i64;
+    let sign_k = (vp2[other_flag_2] - vp1[other_flag_2]).signum() as i64;
     let mut tm_int = 0;
-    let first = unsafe {
-        SingleId::uncheck_new(
-            z,
-            current[pull_index[0]] + offsets_int[0],
-            (current[pull_index[1]] + offsets_int[1]) as u32,
-            (current[pull_index[2]] + offsets_int[2]) as u32,
-        )
-    };
-    let iter = std::iter::once(first).chain((1..=max_steps).map(move |_| {
+    let first = [
+        current[pull_index[0]] + offsets_int[0],
+        current[pull_index[1]] + offsets_int[1],
+        current[pull_index[2]] + offsets_int[2],
+    ];
+    std::iter::once(first).chain((1..=max_steps).map(move |_| {
         let min_wall = (tm_int as f64).min(to1).min(to2);
         if min_wall == tm_int as f64 {
             tm_int += 1;
@@ -164,14 +427,21 @@ pub(crate) fn line_dda(
             to2 += d_o2;
             current[2] += sign_k;
         }
-        unsafe {
-            SingleId::uncheck_new(
-                z,
-                current[pull_index[0]] + offsets_int[0],
-                (current[pull_index[1]] + offsets_int[1]) as u32,
-                (current[pull_index[2]] + offsets_int[2]) as u32,
-            )
-        }
-    }));
-    Ok(iter)
+        [
+            current[pull_index[0]] + offsets_int[0],
+            current[pull_index[1]] + offsets_int[1],
+            current[pull_index[2]] + offsets_int[2],
+        ]
+    }))
+}
+
+/// インデックス空間上のセル `[f, x, y]` を [`SingleId`] へ変換する。
+///
+/// X は東西方向に循環させて正規化し、F または Y が範囲外の場合は `None` を返す。
+pub(crate) fn index_to_single_id(z: u8, [f, x, y]: [i64; 3]) -> Option<SingleId> {
+    let n = 1_i64 << z;
+    if !(0..n).contains(&y) || !(F_MIN[z as usize] as i64..=F_MAX[z as usize] as i64).contains(&f) {
+        return None;
+    }
+    Some(unsafe { SingleId::uncheck_new(z, f as i32, x.rem_euclid(n) as u32, y as u32) })
 }
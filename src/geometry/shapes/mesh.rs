@@ -0,0 +1,239 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::Error,
+    geometry::{coordinate::Coordinate, helpers::coordinate_to_matrix, shapes::triangle::triangle},
+    spatial_id::{constants::MAX_ZOOM_LEVEL, single::SingleId},
+};
+
+/// メッシュをボクセル化する際の充填方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillMode {
+    /// 表面の三角形を覆う空間 ID のみを列挙する。
+    Surface,
+    /// 表面に加えて、メッシュ内部の空間 ID も列挙する。
+    ///
+    /// 内部判定は F 方向の列ごとの交差回数の偶奇で行うため、
+    /// メッシュが閉じている（水密である）ことが前提となる。
+    Solid,
+}
+
+/// 頂点配列と三角形面のインデックスで定義されるメッシュを覆う空間 ID を列挙する。
+///
+/// `faces` の各要素は `vertices` へのインデックス 3 つで 1 枚の三角形を表す。
+/// [`FillMode::Solid`] を指定した場合、各 `(x, y)` 列の中心を通る鉛直線と面との交点を求め、
+/// 交点の偶奇から内部と判定された区間の空間 ID も返す。
+/// 交点が奇数個となった列は閉じていないと見なし、表面のみを返す。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::mesh::{mesh, FillMode};
+/// # use kasane_logic::spatial_id::SpatialId;
+/// // 約 9m × 11m × 10m の直方体
+/// let vertices: Vec<Coordinate> = [
+///     (35.0, 139.0, 0.0),
+///     (35.0, 139.0001, 0.0),
+///     (35.0001, 139.0001, 0.0),
+///     (35.0001, 139.0, 0.0),
+///     (35.0, 139.0, 10.0),
+///     (35.0, 139.0001, 10.0),
+///     (35.0001, 139.0001, 10.0),
+///     (35.0001, 139.0, 10.0),
+/// ]
+/// .iter()
+/// .map(|&(lat, lon, alt)| Coordinate::new(lat, lon, alt).unwrap())
+/// .collect();
+/// let faces = [
+///     [0, 2, 1], [0, 3, 2], // 底面
+///     [4, 5, 6], [4, 6, 7], // 上面
+///     [0, 1, 5], [0, 5, 4],
+///     [1, 2, 6], [1, 6, 5],
+///     [2, 3, 7], [2, 7, 6],
+///     [3, 0, 4], [3, 4, 7],
+/// ];
+///
+/// let surface: Vec<_> = mesh(25, &vertices, &faces, FillMode::Surface).unwrap().collect();
+/// let solid: Vec<_> = mesh(25, &vertices, &faces, FillMode::Solid).unwrap().collect();
+/// assert!(solid.len() > surface.len());
+///
+/// // 直方体の中心付近の空間 ID は内部充填時のみ含まれる
+/// let inner = Coordinate::new(35.00005, 139.00005, 5.0).unwrap().to_single_id(25);
+/// assert!(!surface.contains(&inner));
+/// assert!(solid.contains(&inner));
+/// ```
+///
+/// 隣接する面が共有する辺の上に列の中心がある場合も、交点は 1 つの面でのみ数える:
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::mesh::{mesh, FillMode};
+/// # use kasane_logic::spatial_id::single::SingleId;
+/// // z = 20 で X が 2^19 + 0.25, 2^19 + 1.5, 2^19 + 2.75 となる経度
+/// let unit = 45.0 / 131_072.0;
+/// let (west, middle, east) = (0.25 * unit, 1.5 * unit, 2.75 * unit);
+/// let (south, north) = (-0.0001, 0.0006);
+///
+/// let mut vertices = Vec::new();
+/// for alt in [0.0, 100.0] {
+///     for (lat, lon) in [
+///         (south, west), (south, middle), (south, east),
+///         (north, east), (north, middle), (north, west),
+///     ] {
+///         vertices.push(Coordinate::new(lat, lon, alt).unwrap());
+///     }
+/// }
+/// // 底面と上面は、経度 middle の辺（列 X = 2^19 + 1 の中心を通る）で 2 つに分かれる
+/// let mut faces = vec![
+///     [0, 4, 1], [0, 5, 4], [1, 3, 2], [1, 4, 3],
+///     [6, 7, 10], [6, 10, 11], [7, 8, 9], [7, 9, 10],
+/// ];
+/// for i in 0..6 {
+///     let j = (i + 1) % 6;
+///     faces.push([i, j, j + 6]);
+///     faces.push([i, j + 6, i + 6]);
+/// }
+///
+/// let solid: Vec<_> = mesh(20, &vertices, &faces, FillMode::Solid).unwrap().collect();
+/// let inner = SingleId::new(20, 1, (1 << 19) + 1, (1 << 19) - 1).unwrap();
+/// assert!(solid.contains(&inner));
+/// ```
+///
+/// 範囲外の頂点インデックスの検知:
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::mesh::{mesh, FillMode};
+/// # use kasane_logic::error::Error;
+/// let vertices = [Coordinate::default(); 3];
+/// let result = mesh(20, &vertices, &[[0, 1, 3]], FillMode::Surface);
+/// assert!(matches!(result, Err(Error::VertexIndexOutOfRange { index: 3, len: 3 })));
+/// ```
+pub fn mesh(
+    z: u8,
+    vertices: &[Coordinate],
+    faces: &[[usize; 3]],
+    fill: FillMode,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
+    if z > MAX_ZOOM_LEVEL as u8 {
+        return Err(Error::ZOutOfRange { z });
+    }
+
+    for &index in faces.iter().flatten() {
+        if index >= vertices.len() {
+            return Err(Error::VertexIndexOutOfRange {
+                index,
+                len: vertices.len(),
+            });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut voxels = Vec::new();
+
+    for &[a, b, c] in faces {
+        for id in triangle(z, vertices[a], vertices[b], vertices[c])? {
+            if seen.insert(id.clone()) {
+                voxels.push(id);
+            }
+        }
+    }
+
+    if fill == FillMode::Solid {
+        for id in fill_interior(z, vertices, faces) {
+            if seen.insert(id.clone()) {
+                voxels.push(id);
+            }
+        }
+    }
+
+    Ok(voxels.into_iter())
+}
+
+/// 各 `(x, y)` 列の中心を通る鉛直線と面との交点を集め、偶奇判定で内部の空間 ID を求める。
+fn fill_interior(z: u8, vertices: &[Coordinate], faces: &[[usize; 3]]) -> Vec<SingleId> {
+    let projected: Vec<[f64; 3]> = vertices
+        .iter()
+        .map(|v| coordinate_to_matrix(*v, z))
+        .collect();
+
+    // (x, y) 列ごとの交点の F 座標（連続値）
+    let mut columns: HashMap<(u32, u32), Vec<f64>> = HashMap::new();
+
+    for &[a, b, c] in faces {
+        let (pa, mut pb, mut pc) = (projected[a], projected[b], projected[c]);
+
+        // XY 平面上での符号付き面積の 2 倍。頂点を反時計回りに揃える
+        let mut area = edge_function(pa, pb, pc);
+        if area == 0.0 {
+            // 鉛直な面は鉛直線と交差しない
+            continue;
+        }
+        if area < 0.0 {
+            std::mem::swap(&mut pb, &mut pc);
+            area = -area;
+        }
+
+        let min_x = pa[1].min(pb[1]).min(pc[1]).floor().max(0.0) as u32;
+        let max_x = pa[1].max(pb[1]).max(pc[1]).floor() as u32;
+        let min_y = pa[2].min(pb[2]).min(pc[2]).floor().max(0.0) as u32;
+        let max_y = pa[2].max(pb[2]).max(pc[2]).floor() as u32;
+
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                let p = [0.0, x as f64 + 0.5, y as f64 + 0.5];
+
+                // 各辺の左側にあるか（重心座標の分子）
+                let ea = edge_function(pb, pc, p);
+                let eb = edge_function(pc, pa, p);
+                let ec = edge_function(pa, pb, p);
+                let inside = |e: f64, from: [f64; 3], to: [f64; 3]| {
+                    e > 0.0 || e == 0.0 && owns_edge(from, to)
+                };
+                if !(inside(ea, pb, pc) && inside(eb, pc, pa) && inside(ec, pa, pb)) {
+                    continue;
+                }
+
+                let f = (ea * pa[0] + eb * pb[0] + ec * pc[0]) / area;
+                columns.entry((x, y)).or_default().push(f);
+            }
+        }
+    }
+
+    let mut voxels = Vec::new();
+
+    for ((x, y), mut crossings) in columns {
+        if crossings.len() % 2 != 0 {
+            continue;
+        }
+        crossings.sort_by(f64::total_cmp);
+
+        for pair in crossings.chunks_exact(2) {
+            // セル中心 f + 0.5 が区間内に入る F インデックスを充填する
+            let f_start = (pair[0] - 0.5).ceil() as i32;
+            let f_end = (pair[1] - 0.5).floor() as i32;
+            for f in f_start..=f_end {
+                voxels.push(unsafe { SingleId::uncheck_new(z, f, x, y) });
+            }
+        }
+    }
+
+    voxels.sort();
+    voxels
+}
+
+/// XY 平面上で、`from` から `to` へ向かう辺に対する点 `p` の位置を、辺の左側を正として返す。
+///
+/// 隣接する面で共有される辺の値が、符号の反転を除いて一致するよう端点を一定の順に並べて計算する。
+fn edge_function(from: [f64; 3], to: [f64; 3], p: [f64; 3]) -> f64 {
+    if (from[1], from[2]) > (to[1], to[2]) {
+        return -edge_function(to, from, p);
+    }
+    (to[1] - from[1]) * (p[2] - from[2]) - (to[2] - from[2]) * (p[1] - from[1])
+}
+
+/// 反時計回りの面の辺上にある点を、その面に含めるかを返す。
+///
+/// 点を +Y 方向へ、さらに微小に +X 方向へずらしたものとして扱う。共有される辺は隣接する面で
+/// 向きが逆になるため、辺上や頂点上の点はちょうど 1 つの面にのみ含まれる。
+fn owns_edge(from: [f64; 3], to: [f64; 3]) -> bool {
+    let (dx, dy) = (to[1] - from[1], to[2] - from[2]);
+    dx > 0.0 || dx == 0.0 && dy < 0.0
+}
@@ -1,4 +1,311 @@
+//! 幾何形状を空間 ID の集合へ変換する機能
+//!
+//! 各形状は [`Shape`](crate::geometry::shapes::Shape) トレイトを実装しており、形状の種類によらず共通の方法で
+//! 空間 ID による被覆を求めることができます。
+
+use std::collections::BTreeMap;
+
+use crate::{
+    error::Error,
+    geometry::{coordinate::Coordinate, helpers::is_folded, shapes::coverer::Coverer},
+    spatial_id::{SpatialId, constants::MAX_ZOOM_LEVEL, range::RangeId, single::SingleId},
+};
+
+pub mod above_ground;
+pub mod coverer;
+pub mod csg;
+pub mod cylinder;
+pub mod disk;
+pub mod ellipsoid;
+pub mod half_space;
 pub mod line;
+pub mod mesh;
+pub mod orientation;
+pub mod oriented_box;
+pub mod polygon;
 pub mod sphere;
+pub mod spherical_triangle;
+pub mod spline;
 pub mod triangle;
-pub mod spherical_triangle;
\ No newline at end of file
+
+/// [`Shape::cover_weighted`] で割合を推定する際に、空間 ID を細分化する段階数
+pub const WEIGHT_DEPTH: u8 = 3;
+
+/// 空間 ID と図形との位置関係
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// 空間 ID は図形と共有部分を持たない。
+    Outside,
+    /// 空間 ID は図形の境界と交差している、または位置関係を確定できない。
+    Intersects,
+    /// 空間 ID は図形に完全に含まれる。
+    Inside,
+}
+
+/// 図形を空間 ID で被覆する際の判定基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// 図形と少しでも共有部分を持つ空間 ID をすべて含める。
+    #[default]
+    Conservative,
+    /// 図形に完全に含まれる空間 ID のみを含める。
+    Strict,
+    /// 中心点が図形に含まれる空間 ID のみを含める。
+    CenterIn,
+}
+
+/// 空間 ID で被覆することができる図形が備えるべき性質を定義するトレイト。
+///
+/// 実装側は [`Shape::relation`] と [`Shape::contains`] のみを定義すればよく、
+/// 被覆の列挙は [`SingleId::children`] による階層的な細分化で行われます。
+/// 線や面のように体積を持たない図形は [`Relation::Inside`] を返さないため、
+/// [`Mode::Strict`] および [`Mode::CenterIn`] での被覆は空になります。
+pub trait Shape {
+    /// 空間 ID と図形との位置関係を返す。
+    ///
+    /// [`Relation::Outside`] と [`Relation::Inside`] は確実な場合にのみ返し、
+    /// 判定できない場合は [`Relation::Intersects`] を返す必要がある。
+    fn relation(&self, id: &SingleId) -> Relation;
+
+    /// 点が図形に含まれるかを返す。
+    fn contains(&self, point: &Coordinate) -> bool;
+
+    /// ズームレベル `z` の空間 ID で図形を被覆する。
+    ///
+    /// 戻り値は昇順に整列され、重複を含まない。
+    fn cover(&self, z: u8, mode: Mode) -> Result<Vec<SingleId>, Error> {
+        if z > MAX_ZOOM_LEVEL as u8 {
+            return Err(Error::ZOutOfRange { z });
+        }
+        let mut voxels = refine(self, z, z, mode);
+        voxels.sort();
+        Ok(voxels)
+    }
+
+    /// ズームレベル `z` で図形を被覆し、F 方向に連続する空間 ID を列ごとの [`RangeId`] にまとめる。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::{Mode, Shape, sphere::Sphere};
+    /// let sphere = Sphere::new(Coordinate::new(35.0, 139.0, 100.0).unwrap(), 30.0);
+    /// let singles = sphere.cover(22, Mode::Conservative).unwrap();
+    /// let ranges = sphere.cover_ranges(22, Mode::Conservative).unwrap();
+    ///
+    /// assert!(ranges.len() < singles.len());
+    /// assert_eq!(ranges.iter().map(|r| r.to_single().count()).sum::<usize>(), singles.len());
+    /// ```
+    fn cover_ranges(&self, z: u8, mode: Mode) -> Result<Vec<RangeId>, Error> {
+        let mut columns: BTreeMap<(u32, u32), Vec<i32>> = BTreeMap::new();
+        for id in self.cover(z, mode)? {
+            columns
+                .entry((id.as_x(), id.as_y()))
+                .or_default()
+                .push(id.as_f());
+        }
+
+        let mut ranges = Vec::new();
+        for ((x, y), fs) in columns {
+            // `cover` の戻り値は F について昇順
+            let mut start = fs[0];
+            let mut end = fs[0];
+            for &f in &fs[1..] {
+                if f == end + 1 {
+                    end = f;
+                } else {
+                    ranges.push(unsafe { RangeId::uncheck_new(z, [start, end], [x, x], [y, y]) });
+                    start = f;
+                    end = f;
+                }
+            }
+            ranges.push(unsafe { RangeId::uncheck_new(z, [start, end], [x, x], [y, y]) });
+        }
+        Ok(ranges)
+    }
+
+    /// ズームレベル `min_z..=max_z` の空間 ID を混在させて図形を [`Mode::Conservative`] で被覆する。
+    ///
+    /// 図形に完全に含まれる空間 ID は粗いズームレベルのまま残し、
+    /// 境界と交差する空間 ID のみを `max_z` まで細分化する。
+    /// 空間 ID の個数に上限を設ける場合は [`Coverer`] を使用する。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::{Mode, Shape, sphere::Sphere};
+    /// let sphere = Sphere::new(Coordinate::new(35.0, 139.0, 100.0).unwrap(), 30.0);
+    /// let fine = sphere.cover(22, Mode::Conservative).unwrap();
+    /// let adaptive = sphere.cover_adaptive(18, 22).unwrap();
+    ///
+    /// assert!(adaptive.len() < fine.len());
+    /// assert!(adaptive.iter().any(|id| id.as_z() < 22));
+    /// assert!(adaptive.iter().all(|id| (18..=22).contains(&id.as_z())));
+    /// ```
+    fn cover_adaptive(&self, min_z: u8, max_z: u8) -> Result<Vec<SingleId>, Error> {
+        Ok(Coverer::new(min_z, max_z, usize::MAX)?.cover(self))
+    }
+
+    /// ズームレベル `z` で図形を被覆し、各空間 ID の体積のうち図形に含まれる割合を付けて返す。
+    ///
+    /// 既定の実装では、境界と交差する空間 ID を [`WEIGHT_DEPTH`] 段階まで細分化し、
+    /// 子の位置関係から割合を推定する。割合が 0 の空間 ID は含まれない。
+    /// 体積を持たない図形は、空間 ID の水平な面のうち図形が覆う面積の割合を返すよう上書きする。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::{Mode, Shape, sphere::Sphere};
+    /// let sphere = Sphere::new(Coordinate::new(35.0, 139.0, 100.0).unwrap(), 30.0);
+    /// let weighted = sphere.cover_weighted(21).unwrap();
+    ///
+    /// // 境界の空間 ID は部分的に含まれる
+    /// assert!(weighted.iter().all(|&(_, w)| 0.0 < w && w <= 1.0));
+    /// assert!(weighted.iter().any(|&(_, w)| w < 1.0));
+    ///
+    /// // 割合の合計は球の体積をおおよそ再現する（z=21 の空間 ID は緯度 35° 付近で約 15.7m × 15.7m × 16m）
+    /// let side = 2.0 * std::f64::consts::PI * 6_378_137.0 / 2_f64.powi(21) * 35_f64.to_radians().cos();
+    /// let volume: f64 = weighted.iter().map(|&(_, w)| w * side * side * 16.0).sum();
+    /// let expected = 4.0 / 3.0 * std::f64::consts::PI * 30.0_f64.powi(3);
+    /// assert!((volume - expected).abs() / expected < 0.1);
+    /// ```
+    fn cover_weighted(&self, z: u8) -> Result<Vec<(SingleId, f64)>, Error> {
+        let depth = WEIGHT_DEPTH.min(MAX_ZOOM_LEVEL as u8 - z.min(MAX_ZOOM_LEVEL as u8));
+        Ok(self
+            .cover(z, Mode::Conservative)?
+            .into_iter()
+            .map(|id| {
+                let weight = volume_fraction(self, &id, depth);
+                (id, weight)
+            })
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect())
+    }
+}
+
+/// 図形への参照も図形として扱えるようにし、[`csg`] などで図形を所有せずに合成できるようにする。
+impl<S: Shape + ?Sized> Shape for &S {
+    fn relation(&self, id: &SingleId) -> Relation {
+        (**self).relation(id)
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        (**self).contains(point)
+    }
+
+    fn cover(&self, z: u8, mode: Mode) -> Result<Vec<SingleId>, Error> {
+        (**self).cover(z, mode)
+    }
+
+    fn cover_ranges(&self, z: u8, mode: Mode) -> Result<Vec<RangeId>, Error> {
+        (**self).cover_ranges(z, mode)
+    }
+
+    fn cover_adaptive(&self, min_z: u8, max_z: u8) -> Result<Vec<SingleId>, Error> {
+        (**self).cover_adaptive(min_z, max_z)
+    }
+
+    fn cover_weighted(&self, z: u8) -> Result<Vec<(SingleId, f64)>, Error> {
+        (**self).cover_weighted(z)
+    }
+}
+
+/// ズームレベル 0 の空間 ID（地表より上と下の 2 つ）
+pub(crate) fn root_cells() -> [SingleId; 2] {
+    unsafe {
+        [
+            SingleId::uncheck_new(0, -1, 0, 0),
+            SingleId::uncheck_new(0, 0, 0, 0),
+        ]
+    }
+}
+
+/// ズームレベル 0 から順に空間 ID を細分化し、図形を被覆する空間 ID を求める。
+///
+/// 図形に完全に含まれる空間 ID は、`min_z` 以上であればそのまま採用する。
+fn refine<S: Shape + ?Sized>(shape: &S, min_z: u8, max_z: u8, mode: Mode) -> Vec<SingleId> {
+    let mut voxels = Vec::new();
+    let mut stack = root_cells().to_vec();
+
+    while let Some(id) = stack.pop() {
+        // 折り返した高度の空間 ID は、地表付近の空間 ID と同じ位置を重複して表す
+        if is_folded(&id) {
+            continue;
+        }
+        match shape.relation(&id) {
+            Relation::Outside => {}
+            Relation::Inside => {
+                if id.as_z() >= min_z {
+                    voxels.push(id);
+                } else {
+                    voxels.extend(id.children(min_z - id.as_z()).into_iter().flatten());
+                }
+            }
+            Relation::Intersects => {
+                if id.as_z() < max_z {
+                    stack.extend(id.children(1).into_iter().flatten());
+                } else {
+                    let accept = match mode {
+                        Mode::Conservative => true,
+                        Mode::Strict => false,
+                        Mode::CenterIn => shape.contains(&id.center()),
+                    };
+                    if accept {
+                        voxels.push(id);
+                    }
+                }
+            }
+        }
+    }
+
+    voxels
+}
+
+/// ズームレベル `z` の空間 ID が、`mode` での被覆に含まれるかを返す。
+///
+/// [`Shape::cover_ranges`] を列ごとに直接求める図形で、`refine` と同じ基準を用いるために使用する。
+pub(crate) fn accepts<S: Shape + ?Sized>(shape: &S, id: &SingleId, mode: Mode) -> bool {
+    match shape.relation(id) {
+        Relation::Outside => false,
+        Relation::Inside => true,
+        Relation::Intersects => match mode {
+            Mode::Conservative => true,
+            Mode::Strict => false,
+            Mode::CenterIn => shape.contains(&id.center()),
+        },
+    }
+}
+
+/// 空間 ID を内包する球と、図形の符号付き距離から位置関係を判定する。
+///
+/// `distance` は球の中心から図形までの距離（内部では負）の下限であり、`radius` は球の半径。
+pub(crate) fn relation_from_distance(distance: f64, radius: f64) -> Relation {
+    if distance > radius {
+        Relation::Outside
+    } else if distance < -radius {
+        Relation::Inside
+    } else {
+        Relation::Intersects
+    }
+}
+
+/// 空間 ID の体積のうち図形に含まれる割合を、最大 `depth` 段階まで細分化して推定する。
+///
+/// 細分化しきった空間 ID は、中心点が図形に含まれるかで 0 または 1 とみなす。
+fn volume_fraction<S: Shape + ?Sized>(shape: &S, id: &SingleId, depth: u8) -> f64 {
+    match shape.relation(id) {
+        Relation::Outside => 0.0,
+        Relation::Inside => 1.0,
+        Relation::Intersects if depth == 0 => {
+            if shape.contains(&id.center()) {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        Relation::Intersects => {
+            let children: Vec<SingleId> = id.children(1).into_iter().flatten().collect();
+            children
+                .iter()
+                .map(|child| volume_fraction(shape, child, depth - 1))
+                .sum::<f64>()
+                / children.len() as f64
+        }
+    }
+}
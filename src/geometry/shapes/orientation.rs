@@ -0,0 +1,96 @@
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        ecef::Ecef,
+        enu::{Enu, EnuFrame},
+    },
+};
+
+/// 中心点の局所座標系 ENU における物体の姿勢
+///
+/// 物体の座標軸は前方・左方・上方の右手系とし、すべて 0 のとき前方は北、左方は西、上方は天頂を向く。
+/// 回転は ロール → ピッチ → ヨー の順に、回転前の座標軸の周りに適用する。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Orientation {
+    yaw: f64,
+    pitch: f64,
+    roll: f64,
+}
+
+impl Orientation {
+    /// ヨー・ピッチ・ロール（度）から [`Orientation`] を生成します。
+    ///
+    /// - `yaw`: 前方の方位角（北から時計回り）
+    /// - `pitch`: 前方が上がる向きを正とする仰角
+    /// - `roll`: 右側が下がる向きを正とする傾き
+    pub fn new(yaw: f64, pitch: f64, roll: f64) -> Orientation {
+        Orientation { yaw, pitch, roll }
+    }
+
+    /// ヨー（度）を返します。
+    pub fn as_yaw(&self) -> f64 {
+        self.yaw
+    }
+
+    /// ピッチ（度）を返します。
+    pub fn as_pitch(&self) -> f64 {
+        self.pitch
+    }
+
+    /// ロール（度）を返します。
+    pub fn as_roll(&self) -> f64 {
+        self.roll
+    }
+
+    /// 物体の座標軸（前方・左方・上方）を ENU で返す。
+    fn axes(&self) -> [[f64; 3]; 3] {
+        let (sy, cy) = self.yaw.to_radians().sin_cos();
+        let (sp, cp) = self.pitch.to_radians().sin_cos();
+        let (sr, cr) = self.roll.to_radians().sin_cos();
+
+        // 前方・左方・上方を基底とする回転行列 Rz(-yaw) Ry(-pitch) Rx(roll) の各列
+        let forward = [cy * cp, -sy * cp, sp];
+        let left = [sy * cr - cy * sp * sr, cy * cr + sy * sp * sr, cp * sr];
+        let up = [-cy * sp * cr - sy * sr, sy * sp * cr - cy * sr, cp * cr];
+
+        // (前方, 左方, 上方) = (北, 西, 上) として ENU へ変換する
+        [forward, left, up].map(|[f, l, u]| [-l, f, u])
+    }
+}
+
+/// 中心点と姿勢で定まる物体の座標系
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LocalFrame {
+    frame: EnuFrame,
+    /// 物体の座標軸（前方・左方・上方）を ENU で表した単位ベクトル
+    axes: [Enu; 3],
+}
+
+impl LocalFrame {
+    pub(crate) fn new(center: Coordinate, orientation: Orientation) -> LocalFrame {
+        LocalFrame {
+            frame: EnuFrame::new(center),
+            axes: orientation.axes().map(|[e, n, u]| Enu::new(e, n, u)),
+        }
+    }
+
+    /// ECEF 上の点の、物体の座標系での座標を返す。
+    pub(crate) fn local(&self, [x, y, z]: [f64; 3]) -> [f64; 3] {
+        let enu = self.frame.to_enu(&Ecef::new(x, y, z));
+        self.axes.map(|axis| enu.dot(&axis))
+    }
+}
+
+/// 物体の座標軸に沿った大きさ（半径・半辺長）がすべて正の値であることを確かめる。
+///
+/// 大きさが 0 の軸で正規化すると 0 除算となり、空間 ID との位置関係を判定できない。
+pub(crate) fn validate_extents(extents: [f64; 3]) -> Result<(), Error> {
+    match extents
+        .into_iter()
+        .find(|extent| extent.is_nan() || *extent <= 0.0)
+    {
+        Some(extent) => Err(Error::ExtentOutOfRange { extent }),
+        None => Ok(()),
+    }
+}
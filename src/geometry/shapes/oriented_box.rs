@@ -0,0 +1,136 @@
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        helpers::{cell_bound, cell_vertices, ecef_array, norm},
+        shapes::{
+            Mode, Relation, Shape,
+            orientation::{LocalFrame, Orientation, validate_extents},
+        },
+    },
+    spatial_id::single::SingleId,
+};
+
+/// 中心点の局所座標系 ENU で定義された、任意の姿勢の直方体を覆う空間 ID を列挙する。
+///
+/// `half_extents` は物体の座標軸（前方・左方・上方）に沿った半辺長（m）。
+/// 空間 ID との位置関係は [`SpatialId::vertices`](crate::spatial_id::SpatialId::vertices) で得られる
+/// 8 頂点から保守的に判定する。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{oriented_box::oriented_box, orientation::Orientation};
+/// // 北東を向き、10° 機首を上げた長さ 40m・幅 30m・高さ 10m の機体
+/// let center = Coordinate::new(35.0, 139.0, 150.0).unwrap();
+/// let orientation = Orientation::new(45.0, 10.0, 0.0);
+/// let ids: Vec<_> = oriented_box(23, &center, [20.0, 15.0, 5.0], orientation).unwrap().collect();
+///
+/// assert!(ids.contains(&center.to_single_id(23)));
+/// // 機首の方向（北東・やや上方）の点は含まれ、右舷側（南東）へ 25m 離れた点は含まれない
+/// let nose = Coordinate::new(35.0 + 0.000125, 139.0 + 0.000152, 153.0).unwrap();
+/// let side = Coordinate::new(35.0 - 0.000159, 139.0 + 0.000194, 150.0).unwrap();
+/// assert!(ids.contains(&nose.to_single_id(23)));
+/// assert!(!ids.contains(&side.to_single_id(23)));
+/// ```
+pub fn oriented_box(
+    z: u8,
+    center: &Coordinate,
+    half_extents: [f64; 3],
+    orientation: Orientation,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
+    Ok(OrientedBox::new(*center, half_extents, orientation)?
+        .cover(z, Mode::Conservative)?
+        .into_iter())
+}
+
+/// 中心点・半辺長（m）・姿勢で定義された直方体
+#[derive(Debug, Clone, Copy)]
+pub struct OrientedBox {
+    center: Coordinate,
+    half_extents: [f64; 3],
+    orientation: Orientation,
+    frame: LocalFrame,
+}
+
+impl OrientedBox {
+    /// 中心点・物体の座標軸（前方・左方・上方）に沿った半辺長（m）・姿勢から [`OrientedBox`] を生成します。
+    ///
+    /// # 戻り値
+    /// * 半辺長のいずれかが正の値でない場合は [`Error::ExtentOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::{oriented_box::OrientedBox, orientation::Orientation};
+    /// let center = Coordinate::new(35.0, 139.0, 150.0).unwrap();
+    /// assert!(OrientedBox::new(center, [20.0, 15.0, 5.0], Orientation::default()).is_ok());
+    /// assert!(OrientedBox::new(center, [20.0, 15.0, 0.0], Orientation::default()).is_err());
+    /// assert!(OrientedBox::new(center, [f64::NAN, 15.0, 5.0], Orientation::default()).is_err());
+    /// ```
+    pub fn new(
+        center: Coordinate,
+        half_extents: [f64; 3],
+        orientation: Orientation,
+    ) -> Result<OrientedBox, Error> {
+        validate_extents(half_extents)?;
+        Ok(OrientedBox {
+            center,
+            half_extents,
+            orientation,
+            frame: LocalFrame::new(center, orientation),
+        })
+    }
+
+    /// 中心点を返します。
+    pub fn as_center(&self) -> Coordinate {
+        self.center
+    }
+
+    /// 半辺長を返します。
+    pub fn as_half_extents(&self) -> [f64; 3] {
+        self.half_extents
+    }
+
+    /// 姿勢を返します。
+    pub fn as_orientation(&self) -> Orientation {
+        self.orientation
+    }
+}
+
+impl Shape for OrientedBox {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let (vertices, margin) = cell_vertices(id);
+        let local = vertices.map(|v| self.frame.local(v));
+        let h = self.half_extents;
+
+        // 直方体の面のいずれかに対して、すべての頂点が外側にあれば共有部分を持たない
+        for i in 0..3 {
+            if local.iter().all(|v| v[i] > h[i] + margin)
+                || local.iter().all(|v| v[i] < -h[i] - margin)
+            {
+                return Relation::Outside;
+            }
+        }
+
+        // 空間 ID を内包する球が、直方体から離れている場合
+        let (center, radius) = cell_bound(id);
+        let c = self.frame.local(center);
+        let outside: [f64; 3] = std::array::from_fn(|i| (c[i].abs() - h[i]).max(0.0));
+        if norm(outside) > radius {
+            return Relation::Outside;
+        }
+
+        if local
+            .iter()
+            .all(|v| (0..3).all(|i| v[i].abs() <= h[i] - margin))
+        {
+            Relation::Inside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        let p = self.frame.local(ecef_array((*point).into()));
+        (0..3).all(|i| p[i].abs() <= self.half_extents[i])
+    }
+}
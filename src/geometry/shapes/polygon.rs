@@ -0,0 +1,327 @@
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        helpers::{altitude_to_f, lowest_unfolded_f},
+        shapes::{
+            Mode, Relation, Shape, accepts,
+            spherical_triangle::{Point2, clip_to_tile, polygon_area, project, segments_intersect},
+        },
+    },
+    spatial_id::{SpatialId, constants::MAX_ZOOM_LEVEL, helpers, range::RangeId, single::SingleId},
+};
+
+/// WEBメルカトル平面上の多角形を底面とし、高度 `lower` 以上 `upper` 以下（m）の範囲を持つ角柱
+///
+/// 多角形の辺は WEBメルカトル平面上の線分として扱う。経度 ±180° をまたぐ辺は、
+/// 隣り合う頂点を短い方の経路で結ぶ。自己交差する多角形は偶奇規則で内外を判定する。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{Mode, Shape, polygon::Polygon};
+/// let footprint = vec![
+///     Coordinate::new(35.0, 139.0, 0.0).unwrap(),
+///     Coordinate::new(35.0, 139.01, 0.0).unwrap(),
+///     Coordinate::new(35.01, 139.01, 0.0).unwrap(),
+///     Coordinate::new(35.01, 139.0, 0.0).unwrap(),
+/// ];
+/// let polygon = Polygon::new(footprint, 0.0, 100.0);
+///
+/// let inside = Coordinate::new(35.005, 139.005, 50.0).unwrap();
+/// assert!(polygon.contains(&inside));
+/// assert!(polygon.cover(20, Mode::Strict).unwrap().contains(&inside.to_single_id(20)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Coordinate>,
+    lower: f64,
+    upper: f64,
+}
+
+impl Polygon {
+    /// 底面の頂点と高度の範囲（m）から [`Polygon`] を生成します。
+    ///
+    /// 頂点の高度は使用しません。`lower` と `upper` が逆順の場合は入れ替えます。
+    pub fn new(vertices: Vec<Coordinate>, lower: f64, upper: f64) -> Polygon {
+        Polygon {
+            vertices,
+            lower: lower.min(upper),
+            upper: lower.max(upper),
+        }
+    }
+
+    /// 底面の頂点を返します。
+    pub fn as_vertices(&self) -> &[Coordinate] {
+        &self.vertices
+    }
+
+    /// 高度の下限を返します。
+    pub fn as_lower(&self) -> f64 {
+        self.lower
+    }
+
+    /// 高度の上限を返します。
+    pub fn as_upper(&self) -> f64 {
+        self.upper
+    }
+
+    /// 頂点を投影し、経度 ±180° で分断されないよう X 座標を展開する。
+    fn project(&self, z: u8) -> Vec<Point2> {
+        let n = 2_f64.powi(z as i32);
+        let mut points: Vec<Point2> = Vec::with_capacity(self.vertices.len());
+        for v in &self.vertices {
+            let mut p = project(v, z);
+            if let Some(prev) = points.last() {
+                p.x -= ((p.x - prev.x) / n).round() * n;
+            }
+            points.push(p);
+        }
+        points
+    }
+
+    /// 投影した平面上で、タイルと多角形の位置関係を判定する。
+    fn tile_relation(&self, points: &[Point2], tx: f64, ty: f64) -> Relation {
+        let corners = [
+            Point2 { x: tx, y: ty },
+            Point2 { x: tx + 1.0, y: ty },
+            Point2 {
+                x: tx + 1.0,
+                y: ty + 1.0,
+            },
+            Point2 { x: tx, y: ty + 1.0 },
+        ];
+
+        // 辺同士が交差すれば、タイルは多角形の境界をまたぐ
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            for (j, &c) in corners.iter().enumerate() {
+                if segments_intersect(a, b, c, corners[(j + 1) % 4]) {
+                    return Relation::Intersects;
+                }
+            }
+        }
+
+        let center = Point2 {
+            x: tx + 0.5,
+            y: ty + 0.5,
+        };
+        if point_in_polygon(center, points) {
+            return Relation::Inside;
+        }
+
+        // 多角形全体がタイルの内部にある場合
+        let p = points[0];
+        if p.x > tx && p.x < tx + 1.0 && p.y > ty && p.y < ty + 1.0 {
+            return Relation::Intersects;
+        }
+
+        Relation::Outside
+    }
+
+    /// 多角形を一周ずらした位置も含めて、タイル (X, Y) と多角形の位置関係を判定する。
+    fn column_relation(&self, z: u8, x: u32, y: u32) -> Relation {
+        let points = self.project(z);
+        let n = 2_f64.powi(z as i32);
+        let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+
+        let mut relation = Relation::Outside;
+        for shift in [-n, 0.0, n] {
+            let tx = x as f64 + shift;
+            if tx + 1.0 < min_x || tx > max_x {
+                continue;
+            }
+            match self.tile_relation(&points, tx, y as f64) {
+                Relation::Outside => {}
+                Relation::Inside => return Relation::Inside,
+                Relation::Intersects => relation = Relation::Intersects,
+            }
+        }
+        relation
+    }
+
+    /// 水平位置の関係が `column` である列 (X, Y) のうち、`mode` での被覆に含まれる F の範囲を返す。
+    fn column_range(
+        &self,
+        z: u8,
+        x: u32,
+        y: u32,
+        column: Relation,
+        mode: Mode,
+    ) -> Option<[i32; 2]> {
+        let id = |f: i32| unsafe { SingleId::uncheck_new(z, f, x, y) };
+        let horizontal = match (column, mode) {
+            (Relation::Outside, _) | (Relation::Intersects, Mode::Strict) => false,
+            (Relation::Intersects, Mode::CenterIn) => self.contains_horizontal(&id(0).center()),
+            _ => true,
+        };
+        if !horizontal {
+            return None;
+        }
+
+        // 高度の範囲を含む F の範囲から、被覆に含まれない両端の空間 ID を除く
+        let mut lower = altitude_to_f(self.lower, z).max(lowest_unfolded_f(z));
+        let mut upper = altitude_to_f(self.upper, z);
+        while lower <= upper && !accepts(self, &id(lower), mode) {
+            lower += 1;
+        }
+        while lower <= upper && !accepts(self, &id(upper), mode) {
+            upper -= 1;
+        }
+        (lower <= upper).then_some([lower, upper])
+    }
+
+    /// 高度を除いて、点の水平位置が多角形の内部にあるかを返す。
+    fn contains_horizontal(&self, point: &Coordinate) -> bool {
+        let points = self.project(0);
+        let p = project(point, 0);
+        [-1.0, 0.0, 1.0].into_iter().any(|shift| {
+            point_in_polygon(
+                Point2 {
+                    x: p.x + shift,
+                    y: p.y,
+                },
+                &points,
+            )
+        })
+    }
+}
+
+/// 偶奇規則で点が多角形の内部にあるかを判定する。
+fn point_in_polygon(p: Point2, points: &[Point2]) -> bool {
+    let mut inside = false;
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        if (a.y > p.y) != (b.y > p.y) {
+            let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if p.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+impl Shape for Polygon {
+    fn relation(&self, id: &SingleId) -> Relation {
+        if self.vertices.len() < 3 {
+            return Relation::Outside;
+        }
+
+        let z = id.as_z();
+        let bottom = helpers::altitude(id.as_f() as f64, z);
+        let top = helpers::altitude(id.as_f() as f64 + 1.0, z);
+        if top <= self.lower || bottom > self.upper {
+            return Relation::Outside;
+        }
+
+        match self.column_relation(z, id.as_x(), id.as_y()) {
+            Relation::Inside if self.lower <= bottom && top <= self.upper => Relation::Inside,
+            Relation::Outside => Relation::Outside,
+            _ => Relation::Intersects,
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        if self.vertices.len() < 3 || !(self.lower..=self.upper).contains(&point.as_altitude()) {
+            return false;
+        }
+        self.contains_horizontal(point)
+    }
+
+    /// 底面を覆う列をタイルの階層で求め、列ごとの F の範囲を高度の範囲から直接求める。
+    ///
+    /// 高度の範囲が広い角柱でも、空間 ID を 1 つずつ列挙しない。
+    fn cover_ranges(&self, z: u8, mode: Mode) -> Result<Vec<RangeId>, Error> {
+        if z > MAX_ZOOM_LEVEL as u8 {
+            return Err(Error::ZOutOfRange { z });
+        }
+        if self.vertices.len() < 3 {
+            return Ok(Vec::new());
+        }
+
+        let mut columns = Vec::new();
+        let mut stack = vec![(0_u8, 0_u32, 0_u32)];
+        while let Some((tz, tx, ty)) = stack.pop() {
+            match self.column_relation(tz, tx, ty) {
+                Relation::Outside => {}
+                // 多角形に含まれるタイルの列は、細分化しても多角形に含まれる
+                Relation::Inside => {
+                    let scale = 1_u32 << (z - tz);
+                    for x in tx * scale..(tx + 1) * scale {
+                        for y in ty * scale..(ty + 1) * scale {
+                            columns.push((x, y, Relation::Inside));
+                        }
+                    }
+                }
+                Relation::Intersects if tz < z => {
+                    for (dx, dy) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                        stack.push((tz + 1, tx * 2 + dx, ty * 2 + dy));
+                    }
+                }
+                Relation::Intersects => columns.push((tx, ty, Relation::Intersects)),
+            }
+        }
+        columns.sort_by_key(|&(x, y, _)| (x, y));
+
+        Ok(columns
+            .into_iter()
+            .filter_map(|(x, y, column)| {
+                let f = self.column_range(z, x, y, column, mode)?;
+                Some(unsafe { RangeId::uncheck_new(z, f, [x, x], [y, y]) })
+            })
+            .collect())
+    }
+
+    /// 各空間 ID の水平な面のうち多角形が覆う面積の割合に、高度方向の重なりの割合を掛けて返す。
+    ///
+    /// 面積は WEBメルカトル平面上で多角形をタイルで切り取って正確に求める。
+    /// 自己交差する多角形では、重なった部分の面積が正しく求まらない。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::{Shape, polygon::Polygon};
+    /// let footprint = vec![
+    ///     Coordinate::new(35.0, 139.0, 0.0).unwrap(),
+    ///     Coordinate::new(35.0, 139.01, 0.0).unwrap(),
+    ///     Coordinate::new(35.01, 139.0, 0.0).unwrap(),
+    /// ];
+    /// // z=18 の空間 ID の高さは 128m であるため、高さ 64m の角柱は各空間 ID の半分までを占める
+    /// let polygon = Polygon::new(footprint, 0.0, 64.0);
+    /// let weighted = polygon.cover_weighted(18).unwrap();
+    /// assert!(weighted.iter().all(|&(_, w)| 0.0 < w && w <= 0.5));
+    ///
+    /// let n = 2_f64.powi(18);
+    /// let dx = 0.01 / 360.0 * n;
+    /// let dy = (35.01_f64.to_radians().tan().asinh() - 35.0_f64.to_radians().tan().asinh())
+    ///     / (2.0 * std::f64::consts::PI) * n;
+    /// let total: f64 = weighted.iter().map(|&(_, w)| w).sum();
+    /// let expected = dx * dy / 2.0 * 0.5;
+    /// assert!((total - expected).abs() / expected < 1e-6);
+    /// ```
+    fn cover_weighted(&self, z: u8) -> Result<Vec<(SingleId, f64)>, Error> {
+        let voxels = self.cover(z, Mode::Conservative)?;
+        let points = self.project(z);
+        let n = 2_f64.powi(z as i32);
+        let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+
+        Ok(voxels
+            .into_iter()
+            .map(|id| {
+                let bottom = helpers::altitude(id.as_f() as f64, z);
+                let top = helpers::altitude(id.as_f() as f64 + 1.0, z);
+                let overlap = (top.min(self.upper) - bottom.max(self.lower)).max(0.0);
+
+                let area: f64 = [-n, 0.0, n]
+                    .into_iter()
+                    .map(|shift| id.as_x() as f64 + shift)
+                    .filter(|&tx| tx + 1.0 >= min_x && tx <= max_x)
+                    .map(|tx| polygon_area(&clip_to_tile(&points, tx, id.as_y() as f64)))
+                    .sum();
+                (id, area.min(1.0) * overlap / (top - bottom))
+            })
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect())
+    }
+}
@@ -1,6 +1,12 @@
 use crate::{
-    geometry::{constants::WGS84_A, coordinate::Coordinate, ecef::Ecef},
-    spatial_id::{SpatialId, helpers::Dimension, single::SingleId},
+    error::Error,
+    geometry::{
+        constants::WGS84_A,
+        coordinate::Coordinate,
+        helpers::{altitude_to_f, cell_bound, ecef_array, norm, sub, tile_bounds},
+        shapes::{Relation, Shape, relation_from_distance},
+    },
+    spatial_id::{SpatialId, constants::MAX_ZOOM_LEVEL, helpers::Dimension, single::SingleId},
 };
 
 pub fn voxel_length(z: u8, axis: Dimension) -> f64 {
@@ -15,42 +21,109 @@ pub fn voxel_length(z: u8, axis: Dimension) -> f64 {
 }
 
 /// 指定された中心点と半径で定義される球状領域を覆う空間 ID を列挙する。
-pub fn sphere(z: u8, center: &Coordinate, radius: f64) -> impl Iterator<Item = SingleId> {
-    let voxel_diag_half = voxel_length(z, Dimension::X) * 3.0_f64.sqrt() / 2.0;
-    let center_ecef: Ecef = (*center).into();
-
-    // 球の8頂点 → 探索範囲推定
-    let mut corners = Vec::with_capacity(8);
-    for &sx in &[1.0, -1.0] {
-        for &sy in &[1.0, -1.0] {
-            for &sz in &[1.0, -1.0] {
-                let e = Ecef::new(
-                    center_ecef.as_x() + radius * sx,
-                    center_ecef.as_y() + radius * sy,
-                    center_ecef.as_z() + radius * sz,
-                );
-                if let Ok(id) = e.to_single_id(z) {
-                    corners.push(id);
-                }
-            }
-        }
+///
+/// 探索範囲は球の視半径から求めた緯度・経度の範囲とし、経度 ±180° をまたぐ球や
+/// 極付近の球も扱うことができる。WEBメルカトルの緯度範囲外の部分は除外される。
+///
+/// 経度 180° 付近の球:
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::sphere::sphere;
+/// let center = Coordinate::new(35.0, 179.9999, 0.0).unwrap();
+/// let ids: Vec<_> = sphere(20, &center, 50.0).unwrap().collect();
+///
+/// assert!(ids.len() < 1_000);
+/// assert!(ids.iter().any(|id| id.as_x() == 0));
+/// assert!(ids.iter().any(|id| id.as_x() == (1 << 20) - 1));
+/// ```
+///
+/// 緯度の上限付近の球:
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::sphere::sphere;
+/// let center = Coordinate::new(85.05, 0.0, 0.0).unwrap();
+/// let ids: Vec<_> = sphere(16, &center, 2_000.0).unwrap().collect();
+///
+/// assert!(ids.iter().any(|id| id.as_y() == 0));
+/// ```
+pub fn sphere(
+    z: u8,
+    center: &Coordinate,
+    radius: f64,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
+    if z > MAX_ZOOM_LEVEL as u8 {
+        return Err(Error::ZOutOfRange { z });
     }
 
-    let x_min = corners.iter().map(|v| v.as_x()).min().unwrap();
-    let x_max = corners.iter().map(|v| v.as_x()).max().unwrap();
-    let y_min = corners.iter().map(|v| v.as_y()).min().unwrap();
-    let y_max = corners.iter().map(|v| v.as_y()).max().unwrap();
-    let f_min = corners.iter().map(|v| v.as_f()).min().unwrap();
-    let f_max = corners.iter().map(|v| v.as_f()).max().unwrap();
-
-    (x_min..=x_max)
-        .flat_map(move |x| {
-            (y_min..=y_max).flat_map(move |y| {
-                (f_min..=f_max).map(move |f| unsafe { SingleId::uncheck_new(z, f, x, y) })
-            })
+    let voxel_diag_half = voxel_length(z, Dimension::X) * 3.0_f64.sqrt() / 2.0;
+    let center = *center;
+
+    let f_min = altitude_to_f(center.as_altitude() - radius, z);
+    let f_max = altitude_to_f(center.as_altitude() + radius, z);
+
+    let columns: Vec<(u32, u32)> = tile_bounds(center, radius, z)
+        .map(|bounds| bounds.columns().collect())
+        .unwrap_or_default();
+
+    Ok(columns
+        .into_iter()
+        .flat_map(move |(x, y)| {
+            (f_min..=f_max).map(move |f| unsafe { SingleId::uncheck_new(z, f, x, y) })
         })
         .filter(move |id| {
             let p: Coordinate = id.center();
             center.distance(&p) <= radius + voxel_diag_half
-        })
+        }))
+}
+
+/// 中心点と半径（m）で定義される球
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{Mode, Shape, sphere::Sphere};
+/// let center = Coordinate::new(35.0, 139.0, 100.0).unwrap();
+/// let sphere = Sphere::new(center, 30.0);
+///
+/// let conservative = sphere.cover(22, Mode::Conservative).unwrap();
+/// let center_in = sphere.cover(22, Mode::CenterIn).unwrap();
+/// let strict = sphere.cover(22, Mode::Strict).unwrap();
+///
+/// assert!(conservative.contains(&center.to_single_id(22)));
+/// assert!(strict.iter().all(|id| center_in.contains(id)));
+/// assert!(center_in.iter().all(|id| conservative.contains(id)));
+/// assert!(strict.len() < center_in.len() && center_in.len() < conservative.len());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    center: Coordinate,
+    radius: f64,
+}
+
+impl Sphere {
+    /// 中心点と半径（m）から [`Sphere`] を生成します。
+    pub fn new(center: Coordinate, radius: f64) -> Sphere {
+        Sphere { center, radius }
+    }
+
+    /// 中心点を返します。
+    pub fn as_center(&self) -> Coordinate {
+        self.center
+    }
+
+    /// 半径（m）を返します。
+    pub fn as_radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl Shape for Sphere {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let (center, radius) = cell_bound(id);
+        let distance = norm(sub(center, ecef_array(self.center.into()))) - self.radius;
+        relation_from_distance(distance, radius)
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        self.center.distance(point) <= self.radius
+    }
 }
@@ -1,17 +1,31 @@
-use std::collections::HashSet;
-
+use crate::error::Error;
 use crate::geometry::coordinate::Coordinate;
+use crate::geometry::helpers::altitude_to_f;
+use crate::geometry::shapes::{Mode, Relation, Shape};
+use crate::spatial_id::constants::MAX_ZOOM_LEVEL;
+use crate::spatial_id::range::RangeId;
 use crate::spatial_id::single::SingleId;
 
+/// 球面三角形の高度方向の範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Altitude {
+    /// 高度 `lower` 以上 `upper` 以下（m）の一様な範囲
+    ///
+    /// `lower` と `upper` が逆順の場合は入れ替えて扱う。
+    Band { lower: f64, upper: f64 },
+    /// 各頂点の高度を WEBメルカトル平面上で線形に補間した、傾いた面
+    Vertices,
+}
+
 /// Webメルカトル平面上の点
 #[derive(Debug, Clone, Copy)]
-struct Point2 {
-    x: f64,
-    y: f64,
+pub(crate) struct Point2 {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
 }
 
 /// 小数点切り捨てしない
-fn project(coord: &Coordinate, z: u8) -> Point2 {
+pub(crate) fn project(coord: &Coordinate, z: u8) -> Point2 {
     let n = 2f64.powi(z as i32);
 
     let x = (coord.as_longitude() + 180.0) / 360.0 * n;
@@ -35,13 +49,59 @@ struct Triangle2 {
 }
 
 impl Triangle2 {
-    fn bounding_box(&self) -> (i32, i32, i32, i32) {
-        let min_x = self.a.x.min(self.b.x).min(self.c.x).floor() as i32;
-        let max_x = self.a.x.max(self.b.x).max(self.c.x).ceil() as i32;
-        let min_y = self.a.y.min(self.b.y).min(self.c.y).floor() as i32;
-        let max_y = self.a.y.max(self.b.y).max(self.c.y).ceil() as i32;
+    fn bounding_box(&self) -> (i64, i64, i64, i64) {
+        let min_x = self.a.x.min(self.b.x).min(self.c.x).floor() as i64;
+        let max_x = self.a.x.max(self.b.x).max(self.c.x).ceil() as i64;
+        let min_y = self.a.y.min(self.b.y).min(self.c.y).floor() as i64;
+        let max_y = self.a.y.max(self.b.y).max(self.c.y).ceil() as i64;
         (min_x, max_x, min_y, max_y)
     }
+
+    /// 三角形をタイルで切り取った多角形の頂点を返す。
+    fn clip_to_tile(&self, tx: i64, ty: i64) -> Vec<Point2> {
+        clip_to_tile(&[self.a, self.b, self.c], tx as f64, ty as f64)
+    }
+}
+
+/// 多角形を、線形な関数 `inside` が非負となる半平面で切り取る。
+pub(crate) fn clip_half_plane(polygon: &[Point2], inside: impl Fn(Point2) -> f64) -> Vec<Point2> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &p) in polygon.iter().enumerate() {
+        let q = polygon[(i + 1) % polygon.len()];
+        let (dp, dq) = (inside(p), inside(q));
+        if dp >= 0.0 {
+            clipped.push(p);
+        }
+        if (dp >= 0.0) != (dq >= 0.0) {
+            let t = dp / (dp - dq);
+            clipped.push(Point2 {
+                x: p.x + (q.x - p.x) * t,
+                y: p.y + (q.y - p.y) * t,
+            });
+        }
+    }
+    clipped
+}
+
+/// 多角形をタイル（1×1正方形）で切り取った多角形の頂点を返す。
+pub(crate) fn clip_to_tile(polygon: &[Point2], x0: f64, y0: f64) -> Vec<Point2> {
+    let mut polygon = clip_half_plane(polygon, |p| p.x - x0);
+    polygon = clip_half_plane(&polygon, |p| x0 + 1.0 - p.x);
+    polygon = clip_half_plane(&polygon, |p| p.y - y0);
+    clip_half_plane(&polygon, |p| y0 + 1.0 - p.y)
+}
+
+/// 自己交差しない多角形の面積
+pub(crate) fn polygon_area(polygon: &[Point2]) -> f64 {
+    let twice: f64 = polygon
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let q = polygon[(i + 1) % polygon.len()];
+            p.x * q.y - q.x * p.y
+        })
+        .sum();
+    twice.abs() / 2.0
 }
 
 /// 点が三角形内にあるか
@@ -61,7 +121,7 @@ fn point_in_triangle(p: Point2, t: &Triangle2) -> bool {
     !(has_neg && has_pos)
 }
 
-fn segments_intersect(
+pub(crate) fn segments_intersect(
     a1: Point2,
     a2: Point2,
     b1: Point2,
@@ -97,7 +157,7 @@ fn segments_intersect(
 }
 
 /// タイル（1×1正方形）が三角形と交差するか
-fn tile_intersects_triangle(tx: i32, ty: i32, tri: &Triangle2) -> bool {
+fn tile_intersects_triangle(tx: i64, ty: i64, tri: &Triangle2) -> bool {
     let tile = [
         Point2 { x: tx as f64, y: ty as f64 },
         Point2 { x: tx as f64 + 1.0, y: ty as f64 },
@@ -147,37 +207,205 @@ fn tile_intersects_triangle(tx: i32, ty: i32, tri: &Triangle2) -> bool {
     false
 }
 
-/// Iterator 本体
-pub struct CoverSphericalTriangleIter {
-    z: u8,
+
+/// WEBメルカトル平面上に投影した三角形と、その高度方向の範囲
+#[derive(Debug)]
+struct Prism {
     tri: Triangle2,
-    min_x: i32,
-    max_x: i32,
-    min_y: i32,
-    max_y: i32,
-    cur_x: i32,
-    cur_y: i32,
+    /// 各頂点の高度
+    heights: [f64; 3],
+    altitude: Altitude,
+    /// ズームレベルにおける X 方向のタイル数
+    n: i64,
 }
 
-impl Iterator for CoverSphericalTriangleIter {
-    type Item = SingleId;
+impl Prism {
+    /// 三角形を投影する。
+    ///
+    /// 経度 ±180° をまたぐ三角形が平面上で分断されないよう、頂点 `b`, `c` の X 座標を
+    /// 頂点 `a` から半周以内となるように展開する。
+    fn new(a: &Coordinate, b: &Coordinate, c: &Coordinate, z: u8, altitude: Altitude) -> Prism {
+        let n = 1_i64 << z;
+        let pa = project(a, z);
+        let unwrap = |p: Point2| {
+            let half = n as f64 / 2.0;
+            let x = if p.x - pa.x > half {
+                p.x - n as f64
+            } else if pa.x - p.x > half {
+                p.x + n as f64
+            } else {
+                p.x
+            };
+            Point2 { x, y: p.y }
+        };
+
+        let altitude = match altitude {
+            Altitude::Band { lower, upper } if lower > upper => Altitude::Band {
+                lower: upper,
+                upper: lower,
+            },
+            altitude => altitude,
+        };
+
+        Prism {
+            tri: Triangle2 {
+                a: pa,
+                b: unwrap(project(b, z)),
+                c: unwrap(project(c, z)),
+            },
+            heights: [a.as_altitude(), b.as_altitude(), c.as_altitude()],
+            altitude,
+            n,
+        }
+    }
+
+    /// 走査する展開済みのタイルの範囲を返す。X 方向は最大で 1 周分に制限する。
+    fn bounds(&self) -> (i64, i64, i64, i64) {
+        let (min_x, max_x, min_y, max_y) = self.tri.bounding_box();
+        (
+            min_x,
+            max_x.min(min_x + self.n - 1),
+            min_y.max(0),
+            max_y.min(self.n - 1),
+        )
+    }
+
+    /// 点における面の高度を返す。三角形が平面上で退化している場合は `None` を返す。
+    fn height_at(&self, p: Point2) -> Option<f64> {
+        let Triangle2 { a, b, c } = self.tri;
+        let det = (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y);
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        let lb = ((p.x - a.x) * (c.y - a.y) - (c.x - a.x) * (p.y - a.y)) / det;
+        let lc = ((b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y)) / det;
+        let [ha, hb, hc] = self.heights;
+        Some(ha + lb * (hb - ha) + lc * (hc - ha))
+    }
+
+    /// 展開済みのタイルと三角形の共有部分における高度の範囲を返す。
+    fn tile_altitude(&self, tx: i64, ty: i64) -> Option<[f64; 2]> {
+        if !tile_intersects_triangle(tx, ty, &self.tri) {
+            return None;
+        }
+
+        match self.altitude {
+            Altitude::Band { lower, upper } => Some([lower, upper]),
+            Altitude::Vertices => {
+                let [ha, hb, hc] = self.heights;
+                let lowest = ha.min(hb).min(hc);
+                let highest = ha.max(hb).max(hc);
+
+                // 線形な高度の極値は、切り取られた多角形の頂点で得られる
+                let mut range = [f64::INFINITY, f64::NEG_INFINITY];
+                for p in self.tri.clip_to_tile(tx, ty) {
+                    let Some(h) = self.height_at(p) else {
+                        return Some([lowest, highest]);
+                    };
+                    range = [range[0].min(h), range[1].max(h)];
+                }
+                if range[0] > range[1] {
+                    // 辺で接するだけの場合など、多角形が残らなかった
+                    return Some([lowest, highest]);
+                }
+                Some([range[0].max(lowest), range[1].min(highest)])
+            }
+        }
+    }
+
+    /// X 方向のインデックス `x`（折り返し済み）の列と三角形の共有部分における高度の範囲を返す。
+    fn column_altitude(&self, x: u32, y: u32) -> Option<[f64; 2]> {
+        let (min_x, max_x, _, _) = self.tri.bounding_box();
+        [-self.n, 0, self.n]
+            .into_iter()
+            .map(|shift| x as i64 + shift)
+            .filter(|tx| (min_x..=max_x).contains(tx))
+            .filter_map(|tx| self.tile_altitude(tx, y as i64))
+            .reduce(|a, b| [a[0].min(b[0]), a[1].max(b[1])])
+    }
+
+    /// X 方向のインデックス `x`（折り返し済み）のタイルが三角形に完全に含まれるかを返す。
+    fn tile_inside(&self, x: u32, y: u32) -> bool {
+        [-self.n, 0, self.n].into_iter().any(|shift| {
+            let (x0, y0) = ((x as i64 + shift) as f64, y as f64);
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+                .into_iter()
+                .all(|(dx, dy)| {
+                    point_in_triangle(
+                        Point2 {
+                            x: x0 + dx,
+                            y: y0 + dy,
+                        },
+                        &self.tri,
+                    )
+                })
+        })
+    }
+
+    /// 空間 ID の水平な面のうち三角形が覆う面積の割合に、高度方向の重なりの割合を掛けて返す。
+    ///
+    /// [`Altitude::Vertices`] の場合は、面の高度が空間 ID の高度の範囲に収まる部分の面積の割合を返す。
+    fn cell_fraction(&self, id: &SingleId) -> f64 {
+        let z = id.as_z();
+        let height = 2_f64.powi(25 - z as i32);
+        let bottom = id.as_f() as f64 * height;
+        let top = bottom + height;
+        let (min_x, max_x, _, _) = self.tri.bounding_box();
+        let triangle = [self.tri.a, self.tri.b, self.tri.c];
+
+        let area: f64 = [-self.n, 0, self.n]
+            .into_iter()
+            .map(|shift| id.as_x() as i64 + shift)
+            .filter(|tx| (min_x..=max_x).contains(tx))
+            .map(|tx| {
+                let clipped = clip_to_tile(&triangle, tx as f64, id.as_y() as f64);
+                match self.altitude {
+                    Altitude::Band { lower, upper } => {
+                        let overlap = (top.min(upper) - bottom.max(lower)).max(0.0);
+                        polygon_area(&clipped) * overlap / height
+                    }
+                    Altitude::Vertices => {
+                        // 面の高度は平面上で線形であるため、高度の範囲も半平面で切り取れる
+                        if self.height_at(self.tri.a).is_none() {
+                            return 0.0;
+                        }
+                        let height_at = |p| self.height_at(p).unwrap_or(0.0);
+                        let clipped = clip_half_plane(&clipped, |p| height_at(p) - bottom);
+                        let clipped = clip_half_plane(&clipped, |p| top - height_at(p));
+                        polygon_area(&clipped)
+                    }
+                }
+            })
+            .sum();
+
+        area.min(1.0)
+    }
+}
+
+/// 三角形と共有部分を持つ (X, Y) の列ごとに、F 方向の範囲を [`RangeId`] として返すイテレータ
+pub struct SphericalTriangleRangesIter {
+    z: u8,
+    prism: Prism,
+    min_x: i64,
+    max_x: i64,
+    max_y: i64,
+    cur_x: i64,
+    cur_y: i64,
+}
+
+impl Iterator for SphericalTriangleRangesIter {
+    type Item = RangeId;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.cur_y <= self.max_y {
             while self.cur_x <= self.max_x {
-                let x = self.cur_x;
-                let y = self.cur_y;
+                let x = self.cur_x.rem_euclid(self.prism.n) as u32;
+                let y = self.cur_y as u32;
                 self.cur_x += 1;
 
-                if tile_intersects_triangle(x, y, &self.tri) {
-                    return Some(unsafe {
-                        SingleId::uncheck_new(
-                            self.z,
-                            0,
-                            x as u32,
-                            y as u32,
-                        )
-                    });
+                if let Some([lower, upper]) = self.prism.column_altitude(x, y) {
+                    let f = [altitude_to_f(lower, self.z), altitude_to_f(upper, self.z)];
+                    return Some(unsafe { RangeId::uncheck_new(self.z, f, [x, x], [y, y]) });
                 }
             }
             self.cur_x = self.min_x;
@@ -187,29 +415,293 @@ impl Iterator for CoverSphericalTriangleIter {
     }
 }
 
-/// 球面三角形で覆われるSingleIdをIteratorとして返す**
-pub fn spherical_triangle(
+/// Iterator 本体
+///
+/// 三角形と共有部分を持つタイルごとに、各頂点の高度を結ぶ面が通過する [`SingleId`] を返す。
+pub struct CoverSphericalTriangleIter {
+    ranges: Option<SphericalTriangleRangesIter>,
+    /// 列挙中の列の `[x, y]` と、次に返す F および F の上限
+    column: Option<([u32; 2], [i32; 2])>,
+}
+
+impl Iterator for CoverSphericalTriangleIter {
+    type Item = SingleId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(([x, y], [f, f_max])) = &mut self.column
+                && *f <= *f_max
+            {
+                let z = self.ranges.as_ref()?.z;
+                let id = unsafe { SingleId::uncheck_new(z, *f, *x, *y) };
+                *f += 1;
+                return Some(id);
+            }
+            let range = self.ranges.as_mut()?.next()?;
+            self.column = Some(([range.as_x()[0], range.as_y()[0]], range.as_f()));
+        }
+    }
+}
+
+/// 球面三角形を高度方向の範囲とともに被覆する (X, Y) の列ごとの [`RangeId`] を返す。
+///
+/// 経度 ±180° をまたぐ三角形は、頂点 `a` を基準として短い方の経路で辺を結ぶ。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::spherical_triangle::{spherical_triangle_ranges, Altitude};
+/// let a = Coordinate::new(35.0, 179.99, 0.0).unwrap();
+/// let b = Coordinate::new(35.01, -179.99, 0.0).unwrap();
+/// let c = Coordinate::new(34.99, -179.99, 0.0).unwrap();
+///
+/// let band = Altitude::Band { lower: 0.0, upper: 100.0 };
+/// let ranges: Vec<_> = spherical_triangle_ranges(a, b, c, 16, band).unwrap().collect();
+///
+/// // 経度方向に世界を一周することなく、±180° の両側の列のみを含む
+/// assert!(ranges.len() < 100);
+/// assert!(ranges.iter().any(|r| r.as_x() == [0, 0]));
+/// assert!(ranges.iter().any(|r| r.as_x() == [(1 << 16) - 1, (1 << 16) - 1]));
+/// assert!(ranges.iter().all(|r| r.as_f() == [0, 0]));
+/// ```
+pub fn spherical_triangle_ranges(
     a: Coordinate,
     b: Coordinate,
     c: Coordinate,
     z: u8,
-) -> CoverSphericalTriangleIter {
-    let pa = project(&a, z);
-    let pb = project(&b, z);
-    let pc = project(&c, z);
-
-    let tri = Triangle2 { a: pa, b: pb, c: pc };
+    altitude: Altitude,
+) -> Result<SphericalTriangleRangesIter, Error> {
+    if z > MAX_ZOOM_LEVEL as u8 {
+        return Err(Error::ZOutOfRange { z });
+    }
+    if let Altitude::Band { lower, upper } = altitude {
+        for altitude in [lower, upper] {
+            if altitude.is_nan() {
+                return Err(Error::AltitudeOutOfRange { altitude });
+            }
+        }
+    }
 
-    let (min_x, max_x, min_y, max_y) = tri.bounding_box();
+    let prism = Prism::new(&a, &b, &c, z, altitude);
+    let (min_x, max_x, min_y, max_y) = prism.bounds();
 
-    CoverSphericalTriangleIter {
+    Ok(SphericalTriangleRangesIter {
         z,
-        tri,
+        prism,
         min_x,
         max_x,
-        min_y,
         max_y,
         cur_x: min_x,
         cur_y: min_y,
+    })
+}
+
+/// 球面三角形で覆われるSingleIdをIteratorとして返す
+///
+/// 各頂点の高度を結ぶ面（[`Altitude::Vertices`]）が通過する空間 ID を返す。
+/// `z` が最大ズームレベルを超える場合は何も返さない。
+/// 高度方向の範囲を指定する場合は [`spherical_triangle_with_altitude`] を使用する。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::spherical_triangle::spherical_triangle;
+/// let a = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+/// let b = Coordinate::new(35.0, 139.01, 0.0).unwrap();
+/// let c = Coordinate::new(35.01, 139.0, 0.0).unwrap();
+///
+/// let ids: Vec<_> = spherical_triangle(a, b, c, 16).collect();
+/// assert!(ids.contains(&a.to_single_id(16)));
+/// assert!(ids.iter().all(|id| id.as_f() == 0));
+///
+/// // 頂点の高度に応じた F の空間 ID を返す
+/// let high = [a, b, c].map(|v| Coordinate::new(v.as_latitude(), v.as_longitude(), 3_000.0).unwrap());
+/// let ids: Vec<_> = spherical_triangle(high[0], high[1], high[2], 16).collect();
+/// assert!(ids.contains(&high[0].to_single_id(16)));
+/// assert!(ids.iter().all(|id| id.as_f() == high[0].to_single_id(16).as_f()));
+/// ```
+pub fn spherical_triangle(
+    a: Coordinate,
+    b: Coordinate,
+    c: Coordinate,
+    z: u8,
+) -> CoverSphericalTriangleIter {
+    CoverSphericalTriangleIter {
+        ranges: spherical_triangle_ranges(a, b, c, z, Altitude::Vertices).ok(),
+        column: None,
+    }
+}
+
+/// 球面三角形を高度方向の範囲とともに被覆する SingleId を Iterator として返す
+///
+/// 高度方向の範囲は `altitude` で指定する。[`Altitude::Vertices`] を指定した場合は
+/// 各頂点の高度を結ぶ傾いた面を被覆する。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::spherical_triangle::{spherical_triangle_with_altitude, Altitude};
+/// // 高度 0m から 200m へと傾斜する面
+/// let a = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+/// let b = Coordinate::new(35.0, 139.01, 200.0).unwrap();
+/// let c = Coordinate::new(35.01, 139.0, 0.0).unwrap();
+///
+/// let ids: Vec<_> = spherical_triangle_with_altitude(a, b, c, 20, Altitude::Vertices).unwrap().collect();
+///
+/// assert!(ids.contains(&a.to_single_id(20)));
+/// assert!(ids.contains(&b.to_single_id(20)));
+/// assert!(ids.iter().all(|id| (0..=6).contains(&id.as_f())));
+/// ```
+pub fn spherical_triangle_with_altitude(
+    a: Coordinate,
+    b: Coordinate,
+    c: Coordinate,
+    z: u8,
+    altitude: Altitude,
+) -> Result<impl Iterator<Item = SingleId>, Error> {
+    Ok(
+        spherical_triangle_ranges(a, b, c, z, altitude)?.flat_map(move |range| {
+            let [f_min, f_max] = range.as_f();
+            let [x, _] = range.as_x();
+            let [y, _] = range.as_y();
+            (f_min..=f_max).map(move |f| unsafe { SingleId::uncheck_new(z, f, x, y) })
+        }),
+    )
+}
+
+/// WEBメルカトル平面上の三角形を底面とし、高度方向の範囲を持つ図形
+///
+/// [`Altitude::Band`] の場合は体積を持つ三角柱として扱い、[`Altitude::Vertices`] の場合は
+/// 体積を持たない面として [`Mode::Conservative`](crate::geometry::shapes::Mode::Conservative)
+/// でのみ空間 ID を返す。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{Mode, Shape};
+/// # use kasane_logic::geometry::shapes::spherical_triangle::{spherical_triangle_with_altitude, Altitude, SphericalTriangle};
+/// let a = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+/// let b = Coordinate::new(35.01, 139.0, 30.0).unwrap();
+/// let c = Coordinate::new(35.0, 139.01, 60.0).unwrap();
+///
+/// let mut expected: Vec<_> = spherical_triangle_with_altitude(a, b, c, 18, Altitude::Vertices).unwrap().collect();
+/// expected.sort();
+/// let shape = SphericalTriangle::new(a, b, c, Altitude::Vertices);
+/// assert_eq!(shape.cover(18, Mode::Conservative).unwrap(), expected);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SphericalTriangle {
+    a: Coordinate,
+    b: Coordinate,
+    c: Coordinate,
+    altitude: Altitude,
+}
+
+impl SphericalTriangle {
+    /// 3 つの頂点と高度方向の範囲から [`SphericalTriangle`] を生成します。
+    pub fn new(
+        a: Coordinate,
+        b: Coordinate,
+        c: Coordinate,
+        altitude: Altitude,
+    ) -> SphericalTriangle {
+        SphericalTriangle { a, b, c, altitude }
+    }
+
+    /// 3 つの頂点を返します。
+    pub fn as_vertices(&self) -> [Coordinate; 3] {
+        [self.a, self.b, self.c]
+    }
+
+    /// 高度方向の範囲を返します。
+    pub fn as_altitude(&self) -> Altitude {
+        self.altitude
+    }
+}
+
+impl Shape for SphericalTriangle {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let z = id.as_z();
+        let prism = Prism::new(&self.a, &self.b, &self.c, z, self.altitude);
+
+        let Some([lower, upper]) = prism.column_altitude(id.as_x(), id.as_y()) else {
+            return Relation::Outside;
+        };
+
+        // 空間 ID の高度方向の範囲
+        let height = 2_f64.powi(25 - z as i32);
+        let bottom = id.as_f() as f64 * height;
+        let top = bottom + height;
+
+        if top <= lower || bottom > upper {
+            Relation::Outside
+        } else if matches!(self.altitude, Altitude::Band { .. })
+            && lower <= bottom
+            && top <= upper
+            && prism.tile_inside(id.as_x(), id.as_y())
+        {
+            Relation::Inside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        let Altitude::Band { lower, upper } = self.altitude else {
+            return false;
+        };
+        let (lower, upper) = (lower.min(upper), lower.max(upper));
+        if !(lower..=upper).contains(&point.as_altitude()) {
+            return false;
+        }
+
+        let prism = Prism::new(&self.a, &self.b, &self.c, 0, self.altitude);
+        let p = project(point, 0);
+        [-1.0, 0.0, 1.0].into_iter().any(|shift| {
+            point_in_triangle(
+                Point2 {
+                    x: p.x + shift,
+                    y: p.y,
+                },
+                &prism.tri,
+            )
+        })
+    }
+
+    /// 各空間 ID の水平な面のうち三角形が覆う面積を、平面上で正確に求めて割合とする。
+    ///
+    /// [`Altitude::Band`] の場合は高度方向の重なりの割合を掛けた体積の割合となり、
+    /// [`Altitude::Vertices`] の場合は面が空間 ID の高度の範囲を通過する部分の面積の割合となる。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::Shape;
+    /// # use kasane_logic::geometry::shapes::spherical_triangle::{Altitude, SphericalTriangle};
+    /// let a = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+    /// let b = Coordinate::new(35.01, 139.0, 0.0).unwrap();
+    /// let c = Coordinate::new(35.0, 139.01, 0.0).unwrap();
+    /// let shape = SphericalTriangle::new(a, b, c, Altitude::Band { lower: 0.0, upper: 128.0 });
+    ///
+    /// // z=18 の空間 ID の高さは 128m であるため、割合の合計は三角形を覆うタイル数に等しい
+    /// let weighted = shape.cover_weighted(18).unwrap();
+    /// let total: f64 = weighted.iter().map(|&(_, w)| w).sum();
+    /// assert!(weighted.iter().any(|&(_, w)| w < 1.0));
+    /// assert!(weighted.iter().all(|&(_, w)| w <= 1.0));
+    ///
+    /// // 三角形の面積（タイル単位）
+    /// let n = 2_f64.powi(18);
+    /// let dx = 0.01 / 360.0 * n;
+    /// let dy = (35.01_f64.to_radians().tan().asinh() - 35.0_f64.to_radians().tan().asinh())
+    ///     / (2.0 * std::f64::consts::PI) * n;
+    /// let expected = dx * dy / 2.0;
+    /// assert!((total - expected).abs() / expected < 1e-6);
+    /// ```
+    fn cover_weighted(&self, z: u8) -> Result<Vec<(SingleId, f64)>, Error> {
+        let prism = Prism::new(&self.a, &self.b, &self.c, z, self.altitude);
+        Ok(self
+            .cover(z, Mode::Conservative)?
+            .into_iter()
+            .map(|id| {
+                let weight = prism.cell_fraction(&id);
+                (id, weight)
+            })
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect())
     }
 }
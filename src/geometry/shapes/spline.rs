@@ -0,0 +1,260 @@
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        ecef::Ecef,
+        helpers::{
+            cell_bound, distance_to_segment, ecef_array, geodetic_to_matrix, wrap_longitude,
+        },
+        shapes::{
+            Mode, Relation, Shape,
+            line::{flatten_path, trace_path},
+            refine, relation_from_distance,
+        },
+    },
+    spatial_id::{constants::MAX_ZOOM_LEVEL, single::SingleId},
+};
+
+/// 曲線を ECEF 上の折れ線で近似する際の許容誤差（m）
+const POLYLINE_TOLERANCE: f64 = 0.05;
+
+/// 緯度・経度・高度の空間で定義された曲線
+///
+/// 経度は隣り合う点の間で短い方向に展開してから補間するため、経度 ±180° をまたぐ曲線も扱える。
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    /// 3 次ベジェ曲線を連結した曲線
+    ///
+    /// 制御点は `[始点, 制御点, 制御点, 終点, 制御点, 制御点, 終点, ...]` の順で、
+    /// 個数は `3k + 1`（`k ≥ 1`）でなければならない。
+    Bezier(Vec<Coordinate>),
+    /// すべての点を通過する Catmull-Rom スプライン
+    ///
+    /// 点は 2 個以上必要で、両端では端点を延長して接線を定める。
+    CatmullRom(Vec<Coordinate>),
+}
+
+impl Curve {
+    /// 曲線を 3 次ベジェ曲線の区間の列に変換する。各区間の点は `[lat, lon, alt]` で表す。
+    fn pieces(&self) -> Result<Vec<[[f64; 3]; 4]>, Error> {
+        let points = match self {
+            Curve::Bezier(points) | Curve::CatmullRom(points) => points,
+        };
+        let valid = match self {
+            Curve::Bezier(_) => points.len() >= 4 && points.len() % 3 == 1,
+            Curve::CatmullRom(_) => points.len() >= 2,
+        };
+        if !valid {
+            return Err(Error::ControlPointCount { len: points.len() });
+        }
+
+        // 経度を直前の点から短い方向に展開する
+        let mut unwrapped: Vec<[f64; 3]> = Vec::with_capacity(points.len());
+        for p in points {
+            let lon = match unwrapped.last() {
+                Some(prev) => prev[1] + wrap_longitude(p.as_longitude() - prev[1]),
+                None => p.as_longitude(),
+            };
+            unwrapped.push([p.as_latitude(), lon, p.as_altitude()]);
+        }
+
+        Ok(match self {
+            Curve::Bezier(_) => unwrapped
+                .windows(4)
+                .step_by(3)
+                .map(|w| [w[0], w[1], w[2], w[3]])
+                .collect(),
+            Curve::CatmullRom(_) => {
+                let last = unwrapped.len() - 1;
+                (0..last)
+                    .map(|i| {
+                        let p0 = unwrapped[i.saturating_sub(1)];
+                        let p1 = unwrapped[i];
+                        let p2 = unwrapped[i + 1];
+                        let p3 = unwrapped[(i + 2).min(last)];
+                        // 一様な Catmull-Rom をベジェ曲線の制御点に変換する
+                        let b1 = std::array::from_fn(|k| p1[k] + (p2[k] - p0[k]) / 6.0);
+                        let b2 = std::array::from_fn(|k| p2[k] - (p3[k] - p1[k]) / 6.0);
+                        [p1, b1, b2, p2]
+                    })
+                    .collect()
+            }
+        })
+    }
+}
+
+/// 3 次ベジェ曲線上の点 `[lat, lon, alt]` を返す。緯度は ±90° に丸める。
+fn bezier([p0, p1, p2, p3]: &[[f64; 3]; 4], t: f64) -> [f64; 3] {
+    let s = 1.0 - t;
+    let mut p: [f64; 3] = std::array::from_fn(|k| {
+        s * s * s * p0[k] + 3.0 * s * s * t * p1[k] + 3.0 * s * t * t * p2[k] + t * t * t * p3[k]
+    });
+    p[0] = p[0].clamp(-90.0, 90.0);
+    p
+}
+
+/// 曲線を覆う空間 ID を、曲線の始点側から順に列挙する。
+///
+/// 各区間はインデックス空間上で 1 セルに収まる程度の直線と見なせるまで適応的に分割され、
+/// 分割された区間を連続して走査するため、点を標本化して [`line`](super::line::line) で結ぶ場合と異なり
+/// 継ぎ目での欠落や重複が生じない。
+///
+/// `radius` を指定した場合は、曲線からの距離が `radius`（m）以内の管状の領域を覆う空間 ID を
+/// 昇順に整列して返す。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::spline::{spline, Curve};
+/// let waypoints = vec![
+///     Coordinate::new(35.000, 139.000, 100.0).unwrap(),
+///     Coordinate::new(35.002, 139.003, 120.0).unwrap(),
+///     Coordinate::new(35.000, 139.006, 140.0).unwrap(),
+///     Coordinate::new(35.003, 139.009, 150.0).unwrap(),
+/// ];
+/// let curve = Curve::CatmullRom(waypoints.clone());
+///
+/// let path = spline(21, &curve, None).unwrap();
+/// assert_eq!(path.first(), Some(&waypoints[0].to_single_id(21)));
+/// assert_eq!(path.last(), Some(&waypoints[3].to_single_id(21)));
+/// assert!(waypoints.iter().all(|p| path.contains(&p.to_single_id(21))));
+///
+/// // 連続する空間 ID は常に面で隣接している
+/// for w in path.windows(2) {
+///     let d = (w[0].as_f() - w[1].as_f()).abs()
+///         + (w[0].as_x() as i64 - w[1].as_x() as i64).abs() as i32
+///         + (w[0].as_y() as i64 - w[1].as_y() as i64).abs() as i32;
+///     assert_eq!(d, 1);
+/// }
+///
+/// // 半径 20m の管
+/// let tube = spline(21, &curve, Some(20.0)).unwrap();
+/// assert!(tube.len() > path.len());
+/// assert!(path.iter().all(|id| tube.binary_search(id).is_ok()));
+/// ```
+pub fn spline(z: u8, curve: &Curve, radius: Option<f64>) -> Result<Vec<SingleId>, Error> {
+    if let Some(radius) = radius
+        && (radius.is_nan() || radius < 0.0)
+    {
+        return Err(Error::RadiusOutOfRange { radius });
+    }
+
+    let shape = Spline::new(curve.clone(), radius.unwrap_or(0.0))?;
+    match radius {
+        Some(_) => shape.cover(z, Mode::Conservative),
+        None => {
+            if z > MAX_ZOOM_LEVEL as u8 {
+                return Err(Error::ZOutOfRange { z });
+            }
+            Ok(shape.trace(z))
+        }
+    }
+}
+
+/// 曲線、または曲線から一定の距離以内の管状の領域
+///
+/// 半径が 0 の場合は体積を持たないため、[`Shape`] としては [`Mode::Conservative`] でのみ
+/// 空間 ID を返す。
+#[derive(Debug, Clone)]
+pub struct Spline {
+    curve: Curve,
+    radius: f64,
+    pieces: Vec<[[f64; 3]; 4]>,
+    /// 曲線を近似する ECEF 上の折れ線
+    polyline: Vec<[f64; 3]>,
+}
+
+impl Spline {
+    /// 曲線と管の半径（m）から [`Spline`] を生成します。
+    ///
+    /// 制御点の個数が [`Curve`] の種類に対して不正な場合は [`Error::ControlPointCount`] を返します。
+    pub fn new(curve: Curve, radius: f64) -> Result<Spline, Error> {
+        let pieces = curve.pieces()?;
+
+        let mut polyline: Vec<[f64; 3]> = Vec::new();
+        for piece in &pieces {
+            let path = |t: f64| ecef_array(Ecef::from_geodetic_unchecked(bezier(piece, t)));
+            let points = flatten_path(&path, POLYLINE_TOLERANCE);
+            // 区間の始点は直前の区間の終点と一致する
+            let skip = usize::from(!polyline.is_empty());
+            polyline.extend(points.into_iter().skip(skip));
+        }
+
+        Ok(Spline {
+            curve,
+            radius,
+            pieces,
+            polyline,
+        })
+    }
+
+    /// 曲線を返します。
+    pub fn as_curve(&self) -> &Curve {
+        &self.curve
+    }
+
+    /// 管の半径を返します。
+    pub fn as_radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// 曲線を始点側から順に走査し、通過する空間 ID を列挙する。
+    fn trace(&self, z: u8) -> Vec<SingleId> {
+        let mut voxels: Vec<SingleId> = Vec::new();
+        for piece in &self.pieces {
+            let path = trace_path(z, |t| {
+                let [lat, lon, alt] = bezier(piece, t);
+                geodetic_to_matrix(lat, lon, alt, z)
+            });
+            for id in path {
+                // 区間の継ぎ目では同じセルが続けて現れる
+                if voxels.last() != Some(&id) {
+                    voxels.push(id);
+                }
+            }
+        }
+        voxels
+    }
+
+    /// ECEF 上の点から曲線を近似する折れ線までの距離を返す。
+    fn distance_from(&self, p: [f64; 3]) -> f64 {
+        self.polyline
+            .windows(2)
+            .map(|w| distance_to_segment(p, w[0], w[1]))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+impl Shape for Spline {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let (center, radius) = cell_bound(id);
+        let distance = self.distance_from(center) - self.radius;
+        match relation_from_distance(distance, radius + POLYLINE_TOLERANCE) {
+            Relation::Inside if self.radius == 0.0 => Relation::Intersects,
+            relation => relation,
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        self.radius > 0.0 && self.distance_from(ecef_array((*point).into())) <= self.radius
+    }
+
+    fn cover(&self, z: u8, mode: Mode) -> Result<Vec<SingleId>, Error> {
+        if z > MAX_ZOOM_LEVEL as u8 {
+            return Err(Error::ZOutOfRange { z });
+        }
+        if self.radius > 0.0 {
+            let mut voxels = refine(self, z, z, mode);
+            voxels.sort();
+            return Ok(voxels);
+        }
+        match mode {
+            Mode::Conservative => {
+                let mut voxels = self.trace(z);
+                voxels.sort();
+                voxels.dedup();
+                Ok(voxels)
+            }
+            Mode::Strict | Mode::CenterIn => Ok(Vec::new()),
+        }
+    }
+}
@@ -2,10 +2,19 @@ use std::{cell::RefCell, collections::HashSet, f64::consts::PI, rc::Rc};
 
 use crate::{
     error::Error,
-    geometry::{constants::WGS84_A, coordinate::Coordinate, ecef::Ecef},
-    spatial_id::{constants::MAX_ZOOM_LEVEL, single::SingleId},
+    geometry::{
+        constants::WGS84_A,
+        coordinate::Coordinate,
+        ecef::Ecef,
+        helpers::{cell_bound, cross, distance_to_triangle, dot, ecef_array, sub},
+        shapes::{Mode, Relation, Shape},
+    },
+    spatial_id::{constants::MAX_ZOOM_LEVEL, helpers, single::SingleId},
 };
 
+/// [`Triangle::cover_weighted`] で面積の割合を求める際の、空間 ID 1 辺あたりの標本数
+const FACE_SAMPLES: usize = 8;
+
 /// 指定された 3 点で構成される三角形領域を覆う空間 ID を列挙する。
 pub fn triangle(
     z: u8,
@@ -90,3 +99,146 @@ pub fn triangle(
 
     Ok(iter)
 }
+
+/// 3 点で構成される ECEF 上の平面三角形
+///
+/// 体積を持たないため、[`Shape`] としては [`Mode::Conservative`](crate::geometry::shapes::Mode::Conservative)
+/// でのみ空間 ID を返す。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::shapes::{Mode, Shape, triangle::{triangle, Triangle}};
+/// let a = Coordinate::new(35.0, 139.0, 0.0).unwrap();
+/// let b = Coordinate::new(35.0002, 139.0, 20.0).unwrap();
+/// let c = Coordinate::new(35.0, 139.0002, 40.0).unwrap();
+///
+/// let covered = Triangle::new(a, b, c).cover(22, Mode::Conservative).unwrap();
+/// assert!(triangle(22, a, b, c).unwrap().all(|id| covered.contains(&id)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    a: Coordinate,
+    b: Coordinate,
+    c: Coordinate,
+}
+
+impl Triangle {
+    /// 3 つの頂点から [`Triangle`] を生成します。
+    pub fn new(a: Coordinate, b: Coordinate, c: Coordinate) -> Triangle {
+        Triangle { a, b, c }
+    }
+
+    /// 3 つの頂点を返します。
+    pub fn as_vertices(&self) -> [Coordinate; 3] {
+        [self.a, self.b, self.c]
+    }
+
+    /// 空間 ID の水平な面を格子状に標本化し、鉛直な線分が三角形と交わる割合を返す。
+    fn face_fraction(&self, id: &SingleId) -> f64 {
+        let z = id.as_z();
+        let [a, b, c] = self.as_vertices().map(|v| ecef_array(v.into()));
+        let bottom = helpers::altitude(id.as_f() as f64, z);
+        let top = helpers::altitude(id.as_f() as f64 + 1.0, z);
+
+        let mut hits = 0;
+        for i in 0..FACE_SAMPLES {
+            let lon =
+                helpers::longitude(id.as_x() as f64 + (i as f64 + 0.5) / FACE_SAMPLES as f64, z);
+            for j in 0..FACE_SAMPLES {
+                let lat =
+                    helpers::latitude(id.as_y() as f64 + (j as f64 + 0.5) / FACE_SAMPLES as f64, z);
+                let p = ecef_array(Ecef::from_geodetic_unchecked([lat, lon, bottom]));
+                let q = ecef_array(Ecef::from_geodetic_unchecked([lat, lon, top]));
+                if segment_hits_triangle(p, q, a, b, c) {
+                    hits += 1;
+                }
+            }
+        }
+        hits as f64 / (FACE_SAMPLES * FACE_SAMPLES) as f64
+    }
+}
+
+/// 線分 `pq` が三角形 `abc` と交わるかを判定する（Möller–Trumbore 法）。
+fn segment_hits_triangle(p: [f64; 3], q: [f64; 3], a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> bool {
+    let d = sub(q, p);
+    let e1 = sub(b, a);
+    let e2 = sub(c, a);
+    let h = cross(d, e2);
+    let det = dot(e1, h);
+    // 線分が三角形の面と平行な場合は、面積への寄与がない
+    if det.abs() < f64::EPSILON * dot(d, d).sqrt() * dot(e1, e1).sqrt() * dot(e2, e2).sqrt() {
+        return false;
+    }
+    let s = sub(p, a);
+    let u = dot(s, h) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return false;
+    }
+    let r = cross(s, e1);
+    let v = dot(d, r) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return false;
+    }
+    let t = dot(e2, r) / det;
+    (0.0..=1.0).contains(&t)
+}
+
+impl Shape for Triangle {
+    fn relation(&self, id: &SingleId) -> Relation {
+        let (center, radius) = cell_bound(id);
+        let distance = distance_to_triangle(
+            center,
+            ecef_array(self.a.into()),
+            ecef_array(self.b.into()),
+            ecef_array(self.c.into()),
+        );
+        if distance > radius {
+            Relation::Outside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, _point: &Coordinate) -> bool {
+        false
+    }
+
+    /// 各空間 ID の水平な面のうち、三角形を鉛直方向に投影した際に覆われ、
+    /// かつ三角形が空間 ID の高度の範囲を通過する部分の面積の割合を返す。
+    ///
+    /// 割合は空間 ID の面を 8 × 8 の格子で標本化して求める。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::shapes::{Shape, triangle::Triangle};
+    /// // 高度 10m の水平な三角形
+    /// let a = Coordinate::new(35.0, 139.0, 10.0).unwrap();
+    /// let b = Coordinate::new(35.005, 139.0, 10.0).unwrap();
+    /// let c = Coordinate::new(35.0, 139.005, 10.0).unwrap();
+    /// let weighted = Triangle::new(a, b, c).cover_weighted(20).unwrap();
+    ///
+    /// assert!(weighted.iter().all(|&(_, w)| 0.0 < w && w <= 1.0));
+    /// assert!(weighted.iter().any(|&(_, w)| w == 1.0));
+    /// assert!(weighted.iter().any(|&(_, w)| w < 1.0));
+    ///
+    /// // 割合の合計は三角形を覆うタイル数をおおよそ再現する
+    /// let n = 2_f64.powi(20);
+    /// let dx = 0.005 / 360.0 * n;
+    /// let dy = (35.005_f64.to_radians().tan().asinh() - 35.0_f64.to_radians().tan().asinh())
+    ///     / (2.0 * std::f64::consts::PI) * n;
+    /// let total: f64 = weighted.iter().map(|&(_, w)| w).sum();
+    /// let expected = dx * dy / 2.0;
+    /// assert!((total - expected).abs() / expected < 0.02);
+    /// ```
+    fn cover_weighted(&self, z: u8) -> Result<Vec<(SingleId, f64)>, Error> {
+        Ok(self
+            .cover(z, Mode::Conservative)?
+            .into_iter()
+            .map(|id| {
+                let weight = self.face_fraction(&id);
+                (id, weight)
+            })
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect())
+    }
+}
@@ -0,0 +1,563 @@
+//! 数値標高モデルによる地表面の標高
+//!
+//! ドローンの飛行範囲のように、地表面からの高さ（対地高度）で定められた範囲を扱うために、
+//! 数値標高モデル（DEM）のファイルから地表面の標高を求めます。
+//! 標高はジオイドからの高さであり、楕円体高が必要な場合は
+//! [`Coordinate::orthometric_to_ellipsoidal`](crate::geometry::coordinate::Coordinate::orthometric_to_ellipsoidal)
+//! で変換します。
+
+use std::{f64::consts::PI, path::Path};
+
+use crate::{
+    error::Error,
+    geometry::{coordinate::Coordinate, helpers::read_grid_file},
+    spatial_id::constants::{MAX_ZOOM_LEVEL, XY_MAX},
+};
+
+/// 数値標高モデルの欠測値として広く用いられる値
+const NODATA: f64 = -9999.0;
+
+/// 1 つ以上の数値標高モデルのラスタから成る地表面
+///
+/// 次の形式のファイルを読み込めます。いずれも画素の中心の標高として扱い、
+/// 周囲の 4 つの画素から双一次補間して任意の地点の標高を求めます。
+///
+/// * ESRI ASCII グリッド（緯度・経度の座標系のもの）
+/// * 国土地理院の基盤地図情報 数値標高モデル（JPGIS (GML) 形式の XML）
+/// * 国土地理院の標高タイル（テキスト形式）
+///
+/// 複数のファイルは [`Terrain::extend`] で 1 つにまとめます。ラスタが重なる地点では、
+/// 先に追加されたラスタの標高を用います。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::terrain::Terrain;
+/// // 南西端 35°N 139°E、0.001 度間隔の 3 × 3 の画素
+/// let asc = "\
+/// ncols 3
+/// nrows 3
+/// xllcorner 139.0
+/// yllcorner 35.0
+/// cellsize 0.001
+/// NODATA_value -9999
+/// 30 40 50
+/// 20 30 40
+/// 10 20 -9999
+/// ";
+/// let terrain = Terrain::from_esri_ascii_str(asc).unwrap();
+///
+/// // 中央の画素の中心
+/// let center = Coordinate::new(35.0015, 139.0015, 0.0).unwrap();
+/// assert!((terrain.elevation(&center).unwrap() - 30.0).abs() < 1e-9);
+/// // 北西側の 4 つの画素の中央
+/// let between = Coordinate::new(35.002, 139.001, 0.0).unwrap();
+/// assert!((terrain.elevation(&between).unwrap() - 30.0).abs() < 1e-9);
+///
+/// // 欠測値を含む範囲や、ラスタの範囲外
+/// let southeast = Coordinate::new(35.0003, 139.0027, 0.0).unwrap();
+/// assert!(terrain.elevation(&southeast).is_err());
+/// let outside = Coordinate::new(35.01, 139.0, 0.0).unwrap();
+/// assert!(terrain.elevation(&outside).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Terrain {
+    rasters: Vec<Raster>,
+}
+
+impl Terrain {
+    /// ESRI ASCII グリッド形式のファイルを読み込みます。
+    ///
+    /// # 戻り値
+    /// * ファイルを読み込めない場合は [`Error::GridFileRead`] を返します
+    /// * 解釈できない行がある場合は [`Error::GridFileParse`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::terrain::Terrain;
+    /// let missing = Terrain::from_esri_ascii_file("/nonexistent/dem.asc");
+    /// assert!(matches!(missing, Err(Error::GridFileRead { .. })));
+    ///
+    /// let broken = Terrain::from_esri_ascii_str(
+    ///     "ncols 2\nnrows 2\nxllcorner 139\nyllcorner 35\ncellsize 0.1\n1 2\n3 x\n",
+    /// );
+    /// assert_eq!(broken.unwrap_err(), Error::GridFileParse { line: 7 });
+    ///
+    /// // 列数は整数である必要がある
+    /// let fractional = Terrain::from_esri_ascii_str(
+    ///     "ncols 2.5\nnrows 2\nxllcorner 139\nyllcorner 35\ncellsize 0.1\n1 2 3\n4 5\n",
+    /// );
+    /// assert_eq!(fractional.unwrap_err(), Error::GridFileParse { line: 1 });
+    /// ```
+    pub fn from_esri_ascii_file(path: impl AsRef<Path>) -> Result<Terrain, Error> {
+        Terrain::from_esri_ascii_str(&read_grid_file(path.as_ref())?)
+    }
+
+    /// ESRI ASCII グリッド形式の文字列から読み込みます。
+    ///
+    /// 座標は経度・緯度（度）である必要があります。`xllcorner`・`yllcorner` と
+    /// `xllcenter`・`yllcenter` のいずれの見出しにも対応し、`NODATA_value` が
+    /// 省略された場合は `-9999` を欠測値とします。
+    pub fn from_esri_ascii_str(text: &str) -> Result<Terrain, Error> {
+        let mut header = Vec::new();
+        let mut values = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let error = || Error::GridFileParse { line: index + 1 };
+            let mut fields = line.split_whitespace();
+            let Some(first) = fields.next() else {
+                continue;
+            };
+            if values.is_empty() && first.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                let value: f64 = fields
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(error)?;
+                header.push((first.to_ascii_lowercase(), value, index + 1));
+                continue;
+            }
+            for field in std::iter::once(first).chain(fields) {
+                values.push(field.parse::<f64>().map_err(|_| error())?);
+            }
+        }
+
+        let entry = |key: &str| {
+            header
+                .iter()
+                .find(|(name, _, _)| name == key)
+                .map(|&(_, value, line)| (value, line))
+        };
+        let get = |key: &str| entry(key).map(|(value, _)| value);
+        let (Some((columns, columns_line)), Some((rows, rows_line)), Some(size)) =
+            (entry("ncols"), entry("nrows"), get("cellsize"))
+        else {
+            return Err(Error::GridFileParse { line: 1 });
+        };
+        let (west, south) = match (get("xllcorner"), get("yllcorner")) {
+            (Some(x), Some(y)) => (x, y),
+            _ => match (get("xllcenter"), get("yllcenter")) {
+                (Some(x), Some(y)) => (x - size / 2.0, y - size / 2.0),
+                _ => return Err(Error::GridFileParse { line: 1 }),
+            },
+        };
+        let nodata = get("nodata_value").unwrap_or(NODATA);
+        let values = values
+            .into_iter()
+            .map(|v| if v == nodata { f64::NAN } else { v })
+            .collect();
+
+        let georeference = Georeference::Geographic {
+            north: south + rows * size,
+            west,
+            step: [size, size],
+        };
+        Raster::new(
+            georeference,
+            [rows, columns],
+            [rows_line, columns_line],
+            values,
+            text,
+        )
+        .map(Terrain::single)
+    }
+
+    /// 国土地理院の基盤地図情報 数値標高モデル（JPGIS (GML) 形式）のファイルを読み込みます。
+    ///
+    /// # 戻り値
+    /// * ファイルを読み込めない場合は [`Error::GridFileRead`] を返します
+    /// * 解釈できない要素や行がある場合は [`Error::GridFileParse`] を返します
+    pub fn from_gsi_dem_xml_file(path: impl AsRef<Path>) -> Result<Terrain, Error> {
+        Terrain::from_gsi_dem_xml_str(&read_grid_file(path.as_ref())?)
+    }
+
+    /// 国土地理院の基盤地図情報 数値標高モデル（JPGIS (GML) 形式）の文字列から読み込みます。
+    ///
+    /// `gml:Envelope` の範囲を `gml:GridEnvelope` の画素数で等分し、`gml:tupleList` の標高を
+    /// `gml:startPoint` の画素から北西端を起点に西から東、北から南の順に割り当てます。
+    /// `-9999.` は欠測値として扱います。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::terrain::Terrain;
+    /// let xml = r#"<Dataset><DEM><coverage>
+    /// <gml:boundedBy><gml:Envelope srsName="fguuid:jgd2011.bl">
+    /// <gml:lowerCorner>35.0 139.0</gml:lowerCorner>
+    /// <gml:upperCorner>35.002 139.003</gml:upperCorner>
+    /// </gml:Envelope></gml:boundedBy>
+    /// <gml:gridDomain><gml:Grid dimension="2"><gml:limits><gml:GridEnvelope>
+    /// <gml:low>0 0</gml:low><gml:high>2 1</gml:high>
+    /// </gml:GridEnvelope></gml:limits></gml:Grid></gml:gridDomain>
+    /// <gml:rangeSet><gml:DataBlock><gml:tupleList>
+    /// 地表面,12.50
+    /// 地表面,13.50
+    /// 地表面,14.50
+    /// 地表面,11.50
+    /// </gml:tupleList></gml:DataBlock></gml:rangeSet>
+    /// <gml:coverageFunction><gml:GridFunction>
+    /// <gml:sequenceRule order="+x-y">Linear</gml:sequenceRule>
+    /// <gml:startPoint>1 0</gml:startPoint>
+    /// </gml:GridFunction></gml:coverageFunction>
+    /// </coverage></DEM></Dataset>"#;
+    /// let terrain = Terrain::from_gsi_dem_xml_str(xml).unwrap();
+    ///
+    /// // 北側の行の中央の画素（tupleList の 1 つ目の値）
+    /// let north = Coordinate::new(35.0015, 139.0015, 0.0).unwrap();
+    /// assert!((terrain.elevation(&north).unwrap() - 12.5).abs() < 1e-9);
+    /// // 南側の行の中央の画素（tupleList の 4 つ目の値）
+    /// let south = Coordinate::new(35.0005, 139.0015, 0.0).unwrap();
+    /// assert!((terrain.elevation(&south).unwrap() - 11.5).abs() < 1e-9);
+    /// ```
+    pub fn from_gsi_dem_xml_str(text: &str) -> Result<Terrain, Error> {
+        let end_of_text = Error::GridFileParse {
+            line: text.lines().count(),
+        };
+        let pair = |tag: &str| -> Option<[f64; 2]> {
+            let (_, content) = element(text, tag)?;
+            let mut fields = content.split_whitespace().map(str::parse::<f64>);
+            Some([fields.next()?.ok()?, fields.next()?.ok()?])
+        };
+        let (Some([south, west]), Some([north, east]), Some([high_x, high_y])) = (
+            pair("gml:lowerCorner"),
+            pair("gml:upperCorner"),
+            pair("gml:high"),
+        ) else {
+            return Err(end_of_text);
+        };
+        let [start_x, start_y] = pair("gml:startPoint").unwrap_or([0.0, 0.0]);
+        let Some((offset, tuples)) = element(text, "gml:tupleList") else {
+            return Err(end_of_text);
+        };
+
+        let (columns, rows) = (high_x + 1.0, high_y + 1.0);
+        let high_line = element(text, "gml:high")
+            .map_or(1, |(offset, _)| text[..offset].matches('\n').count() + 1);
+        let mut values = vec![f64::NAN; (columns * rows).max(0.0) as usize];
+        let first_line = text[..offset].matches('\n').count();
+        let mut position = (start_y * columns + start_x) as usize;
+        for (index, tuple) in tuples.lines().enumerate() {
+            let tuple = tuple.trim();
+            if tuple.is_empty() {
+                continue;
+            }
+            let error = || Error::GridFileParse {
+                line: first_line + index + 1,
+            };
+            let value: f64 = tuple
+                .rsplit(',')
+                .next()
+                .and_then(|v| v.trim().parse().ok())
+                .ok_or_else(error)?;
+            *values.get_mut(position).ok_or_else(error)? =
+                if value <= NODATA { f64::NAN } else { value };
+            position += 1;
+        }
+
+        let georeference = Georeference::Geographic {
+            north,
+            west,
+            step: [(north - south) / rows, (east - west) / columns],
+        };
+        Raster::new(georeference, [rows, columns], [high_line; 2], values, text)
+            .map(Terrain::single)
+    }
+
+    /// 国土地理院の標高タイル（テキスト形式）のファイルを、タイル座標 (`z`, `x`, `y`) の
+    /// タイルとして読み込みます。
+    ///
+    /// # 戻り値
+    /// * ファイルを読み込めない場合は [`Error::GridFileRead`] を返します
+    /// * 解釈できない行がある場合は [`Error::GridFileParse`] を返します
+    /// * タイル座標が範囲外の場合は、対応する `Error` を返します
+    pub fn from_gsi_tile_file(
+        path: impl AsRef<Path>,
+        z: u8,
+        x: u32,
+        y: u32,
+    ) -> Result<Terrain, Error> {
+        Terrain::from_gsi_tile_str(&read_grid_file(path.as_ref())?, z, x, y)
+    }
+
+    /// 国土地理院の標高タイル（テキスト形式）の文字列を、タイル座標 (`z`, `x`, `y`) の
+    /// タイルとして読み込みます。
+    ///
+    /// 各行は北から順にタイルの画素の行を表し、西から東へカンマ区切りで標高が並びます。
+    /// `e` は欠測値として扱います。
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::terrain::Terrain;
+    /// # use kasane_logic::spatial_id::{SpatialId, single::SingleId};
+    /// // ズームレベル 14 のタイルを 2 × 2 の画素に分割したもの
+    /// let csv = "100.0,110.0\n120.0,e\n";
+    /// let terrain = Terrain::from_gsi_tile_str(csv, 14, 14_552, 6_451).unwrap();
+    ///
+    /// // 北西の画素（ズームレベル 15 のタイル）の中心
+    /// let id = SingleId::new(15, 0, 29_104, 12_902).unwrap();
+    /// assert!((terrain.elevation(&id.center()).unwrap() - 100.0).abs() < 1e-9);
+    ///
+    /// assert!(Terrain::from_gsi_tile_str(csv, 14, 20_000, 6_451).is_err());
+    ///
+    /// // 各行の画素数は揃っている必要がある
+    /// assert_eq!(
+    ///     Terrain::from_gsi_tile_str("1,2,3\n4,5\n", 14, 14_552, 6_451),
+    ///     Err(Error::GridFileParse { line: 2 })
+    /// );
+    /// ```
+    pub fn from_gsi_tile_str(text: &str, z: u8, x: u32, y: u32) -> Result<Terrain, Error> {
+        if z as usize > MAX_ZOOM_LEVEL {
+            return Err(Error::ZOutOfRange { z });
+        }
+        if x > XY_MAX[z as usize] {
+            return Err(Error::XOutOfRange { z, x });
+        }
+        if y > XY_MAX[z as usize] {
+            return Err(Error::YOutOfRange { z, y });
+        }
+
+        let mut rows = 0.0_f64;
+        let mut columns = None;
+        let mut values = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // 画素数が 1 行目と異なる行があると、以降の画素の位置がずれる
+            let fields = line.split(',').count();
+            if *columns.get_or_insert(fields) != fields {
+                return Err(Error::GridFileParse { line: index + 1 });
+            }
+            for field in line.split(',').map(str::trim) {
+                let value = match field {
+                    "e" => f64::NAN,
+                    _ => field
+                        .parse()
+                        .map_err(|_| Error::GridFileParse { line: index + 1 })?,
+                };
+                values.push(value);
+            }
+            rows += 1.0;
+        }
+        let columns = columns.unwrap_or(0) as f64;
+
+        let georeference = Georeference::WebMercator { z, x, y };
+        let last_line = text.lines().count();
+        Raster::new(georeference, [rows, columns], [last_line; 2], values, text)
+            .map(Terrain::single)
+    }
+
+    fn single(raster: Raster) -> Terrain {
+        Terrain {
+            rasters: vec![raster],
+        }
+    }
+
+    /// 他の [`Terrain`] のラスタを追加します。
+    pub fn extend(&mut self, other: Terrain) {
+        self.rasters.extend(other.rasters);
+    }
+
+    /// ラスタの個数を返します。
+    pub fn len(&self) -> usize {
+        self.rasters.len()
+    }
+
+    /// ラスタを持たない場合に `true` を返します。
+    pub fn is_empty(&self) -> bool {
+        self.rasters.is_empty()
+    }
+
+    /// 座標の地表面の標高（m）を返します。座標の高度は使用しません。
+    ///
+    /// いずれのラスタの範囲にも含まれない場合や、周囲の画素が欠測値の場合は
+    /// [`Error::GridOutOfRange`] を返します。
+    pub fn elevation(&self, coordinate: &Coordinate) -> Result<f64, Error> {
+        let (latitude, longitude) = (coordinate.as_latitude(), coordinate.as_longitude());
+        self.rasters
+            .iter()
+            .find_map(|raster| raster.sample(latitude, longitude))
+            .ok_or(Error::GridOutOfRange {
+                latitude,
+                longitude,
+            })
+    }
+
+    /// 緯度・経度の範囲（度）における地表面の標高の最小値と最大値を返す。
+    ///
+    /// 2 つ目の値は、範囲全体の標高が求まる場合に `true` となる。
+    /// 範囲内の標高が 1 つも求まらない場合は `None` を返す。
+    pub(crate) fn elevation_bounds(
+        &self,
+        [south, north]: [f64; 2],
+        [west, east]: [f64; 2],
+    ) -> Option<([f64; 2], bool)> {
+        self.rasters
+            .iter()
+            .filter_map(|raster| raster.bounds([south, north], [west, east]))
+            .reduce(
+                |([min_a, max_a], complete_a), ([min_b, max_b], complete_b)| {
+                    (
+                        [min_a.min(min_b), max_a.max(max_b)],
+                        complete_a || complete_b,
+                    )
+                },
+            )
+    }
+}
+
+/// 画素の中心に標高を持つ、等間隔のラスタ
+#[derive(Debug, Clone, PartialEq)]
+struct Raster {
+    georeference: Georeference,
+    rows: usize,
+    columns: usize,
+    /// 北から南へ、各行は西から東へ並べた標高（m）。欠測値は `NaN`
+    values: Vec<f64>,
+}
+
+/// ラスタの画素と緯度・経度との対応
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Georeference {
+    /// 北西端の緯度・経度と、緯度方向・経度方向の画素の大きさ（度）
+    Geographic {
+        north: f64,
+        west: f64,
+        step: [f64; 2],
+    },
+    /// Web メルカトルのタイル座標。タイル全体をラスタの画素数で等分する
+    WebMercator { z: u8, x: u32, y: u32 },
+}
+
+impl Raster {
+    /// 行数・列数が整数でない場合はそれらを定めた行 `size_lines` の位置で、
+    /// 画素数が見出しと一致しない場合は最終行の位置で [`Error::GridFileParse`] を返す。
+    fn new(
+        georeference: Georeference,
+        [rows, columns]: [f64; 2],
+        size_lines: [usize; 2],
+        values: Vec<f64>,
+        text: &str,
+    ) -> Result<Raster, Error> {
+        for (size, line) in [rows, columns].into_iter().zip(size_lines) {
+            if size.fract() != 0.0 {
+                return Err(Error::GridFileParse { line });
+            }
+        }
+        let valid_step = match georeference {
+            Georeference::Geographic { step, .. } => step.iter().all(|s| *s > 0.0),
+            Georeference::WebMercator { .. } => true,
+        };
+        if !valid_step || rows < 2.0 || columns < 2.0 || rows * columns != values.len() as f64 {
+            return Err(Error::GridFileParse {
+                line: text.lines().count(),
+            });
+        }
+        Ok(Raster {
+            georeference,
+            rows: rows as usize,
+            columns: columns as usize,
+            values,
+        })
+    }
+
+    /// 緯度・経度を、北西端を原点とする画素単位の（行, 列）の連続値に変換する。
+    fn pixel(&self, latitude: f64, longitude: f64) -> [f64; 2] {
+        match self.georeference {
+            Georeference::Geographic { north, west, step } => {
+                [(north - latitude) / step[0], (longitude - west) / step[1]]
+            }
+            Georeference::WebMercator { z, x, y } => {
+                let n = 2_f64.powi(z as i32);
+                let tx = (longitude + 180.0) / 360.0 * n;
+                let ty = (1.0 - latitude.to_radians().tan().asinh() / PI) / 2.0 * n;
+                [
+                    (ty - y as f64) * self.rows as f64,
+                    (tx - x as f64) * self.columns as f64,
+                ]
+            }
+        }
+    }
+
+    /// 周囲の 4 つの画素の中心から双一次補間した標高を返す。
+    fn sample(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        let [row, column] = self.pixel(latitude, longitude);
+        let (rows, columns) = (self.rows as f64, self.columns as f64);
+        if !(0.0..=rows).contains(&row) || !(0.0..=columns).contains(&column) {
+            return None;
+        }
+        self.interpolate(row, column)
+    }
+
+    /// 画素単位の（行, 列）の位置の標高を双一次補間する。
+    ///
+    /// 外周の画素の中心より外側では、外周の画素の標高をそのまま延長する。
+    fn interpolate(&self, row: f64, column: f64) -> Option<f64> {
+        let u = (row - 0.5).clamp(0.0, (self.rows - 1) as f64);
+        let v = (column - 0.5).clamp(0.0, (self.columns - 1) as f64);
+        let i = (u.floor() as usize).min(self.rows - 2);
+        let j = (v.floor() as usize).min(self.columns - 2);
+        let (s, t) = (u - i as f64, v - j as f64);
+
+        // 重みが無視できる画素は欠測値であっても用いない
+        let weights = [(1.0 - s) * (1.0 - t), (1.0 - s) * t, s * (1.0 - t), s * t];
+        let mut elevation = 0.0;
+        for (k, weight) in weights.into_iter().enumerate() {
+            if weight < 1e-9 {
+                continue;
+            }
+            let value = self.values[(i + k / 2) * self.columns + j + k % 2];
+            if value.is_nan() {
+                return None;
+            }
+            elevation += weight * value;
+        }
+        Some(elevation)
+    }
+
+    /// 緯度・経度の範囲のうちラスタに含まれる部分における、標高の最小値と最大値を返す。
+    fn bounds(&self, [south, north]: [f64; 2], [west, east]: [f64; 2]) -> Option<([f64; 2], bool)> {
+        let [top, left] = self.pixel(north, west);
+        let [bottom, right] = self.pixel(south, east);
+        let (rows, columns) = (self.rows as f64, self.columns as f64);
+        if bottom < 0.0 || top > rows || right < 0.0 || left > columns {
+            return None;
+        }
+        let mut complete = top >= 0.0 && bottom <= rows && left >= 0.0 && right <= columns;
+
+        // 双一次補間は画素の中心を結ぶ格子の各区画で軸ごとに単調であるため、
+        // 範囲の端と画素の中心を通る線での値を調べれば最小値と最大値が求まる
+        let positions = |start: f64, end: f64, len: f64| {
+            let (start, end) = (start.clamp(0.0, len), end.clamp(0.0, len));
+            let centers = (start.floor() as usize..len as usize)
+                .map(|k| k as f64 + 0.5)
+                .filter(move |&p| start < p && p < end);
+            std::iter::once(start)
+                .chain(centers)
+                .chain(std::iter::once(end))
+                .collect::<Vec<f64>>()
+        };
+        let rows = positions(top, bottom, rows);
+        let columns = positions(left, right, columns);
+
+        let mut bounds: Option<[f64; 2]> = None;
+        for &row in &rows {
+            for &column in &columns {
+                let Some(value) = self.interpolate(row, column) else {
+                    complete = false;
+                    continue;
+                };
+                bounds = Some(match bounds {
+                    Some([min, max]) => [min.min(value), max.max(value)],
+                    None => [value, value],
+                });
+            }
+        }
+        bounds.map(|bounds| (bounds, complete))
+    }
+}
+
+/// XML の要素 `tag` の内容と、その開始位置（バイト）を返す。
+fn element<'a>(text: &'a str, tag: &str) -> Option<(usize, &'a str)> {
+    let open = text.find(&format!("<{tag}"))?;
+    let start = open + text[open..].find('>')? + 1;
+    let end = start + text[start..].find(&format!("</{tag}>"))?;
+    Some((start, &text[start..end]))
+}
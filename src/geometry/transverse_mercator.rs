@@ -0,0 +1,135 @@
+use crate::geometry::ellipsoid::Ellipsoid;
+
+/// 楕円体の横メルカトル図法（Gauss–Krüger 図法）
+///
+/// 河瀬 (2011) による Krüger の級数を扁平率の 5 次まで用いて、緯度・経度と
+/// 平面上の座標を相互に変換する。中央子午線から経度差 40 度程度まで 1mm 以下の精度で求まる。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TransverseMercator {
+    /// 中央子午線の縮尺係数を含めた、等角緯度の球の半径に相当する係数
+    radius: f64,
+    /// 原点の赤道からの子午線弧長に縮尺係数を乗じたもの
+    origin_arc: f64,
+    central_meridian: f64,
+    n: f64,
+    alpha: [f64; 5],
+    beta: [f64; 5],
+    delta: [f64; 6],
+}
+
+impl TransverseMercator {
+    /// 楕円体・中央子午線の縮尺係数・原点の緯度と経度（度）から生成する。
+    pub(crate) fn new(
+        ellipsoid: &Ellipsoid,
+        scale: f64,
+        origin_latitude: f64,
+        central_meridian: f64,
+    ) -> TransverseMercator {
+        let f = ellipsoid.as_f();
+        let n = f / (2.0 - f);
+        let [n2, n3, n4, n5, n6] = [2, 3, 4, 5, 6].map(|k| n.powi(k));
+
+        let alpha = [
+            n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0 + 41.0 * n4 / 180.0 - 127.0 * n5 / 288.0,
+            13.0 * n2 / 48.0 - 3.0 * n3 / 5.0 + 557.0 * n4 / 1440.0 + 281.0 * n5 / 630.0,
+            61.0 * n3 / 240.0 - 103.0 * n4 / 140.0 + 15061.0 * n5 / 26880.0,
+            49561.0 * n4 / 161280.0 - 179.0 * n5 / 168.0,
+            34729.0 * n5 / 80640.0,
+        ];
+        let beta = [
+            n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0 - n4 / 360.0 - 81.0 * n5 / 512.0,
+            n2 / 48.0 + n3 / 15.0 - 437.0 * n4 / 1440.0 + 46.0 * n5 / 105.0,
+            17.0 * n3 / 480.0 - 37.0 * n4 / 840.0 - 209.0 * n5 / 4480.0,
+            4397.0 * n4 / 161280.0 - 11.0 * n5 / 504.0,
+            4583.0 * n5 / 161280.0,
+        ];
+        let delta = [
+            2.0 * n - 2.0 * n2 / 3.0 - 2.0 * n3 + 116.0 * n4 / 45.0 + 26.0 * n5 / 45.0
+                - 2854.0 * n6 / 675.0,
+            7.0 * n2 / 3.0 - 8.0 * n3 / 5.0 - 227.0 * n4 / 45.0
+                + 2704.0 * n5 / 315.0
+                + 2323.0 * n6 / 945.0,
+            56.0 * n3 / 15.0 - 136.0 * n4 / 35.0 - 1262.0 * n5 / 105.0 + 73814.0 * n6 / 2835.0,
+            4279.0 * n4 / 630.0 - 332.0 * n5 / 35.0 - 399572.0 * n6 / 14175.0,
+            4174.0 * n5 / 315.0 - 144838.0 * n6 / 6237.0,
+            601676.0 * n6 / 22275.0,
+        ];
+
+        // 子午線弧長の級数
+        let a0 = 1.0 + n2 / 4.0 + n4 / 64.0;
+        let arc = [
+            -3.0 / 2.0 * (n - n3 / 8.0 - n5 / 64.0),
+            15.0 / 16.0 * (n2 - n4 / 4.0),
+            -35.0 / 48.0 * (n3 - 5.0 * n5 / 16.0),
+            315.0 / 512.0 * n4,
+            -693.0 / 1280.0 * n5,
+        ];
+        let phi0 = origin_latitude.to_radians();
+        let factor = scale * ellipsoid.as_a() / (1.0 + n);
+        let origin_arc = factor
+            * (a0 * phi0
+                + (1..=5)
+                    .map(|j| arc[j - 1] * (2.0 * j as f64 * phi0).sin())
+                    .sum::<f64>());
+
+        TransverseMercator {
+            radius: factor * a0,
+            origin_arc,
+            central_meridian,
+            n,
+            alpha,
+            beta,
+            delta,
+        }
+    }
+
+    /// 緯度・経度（度）を、原点からの北向きの距離 `x` と東向きの距離 `y`（m）へ変換する。
+    pub(crate) fn forward(&self, latitude: f64, longitude: f64) -> [f64; 2] {
+        let phi = latitude.to_radians();
+        let (lambda_s, lambda_c) = (longitude - self.central_meridian).to_radians().sin_cos();
+
+        // 等角緯度の正接
+        let k = 2.0 * self.n.sqrt() / (1.0 + self.n);
+        let t = (phi.sin().atanh() - k * (k * phi.sin()).atanh()).sinh();
+        let t_bar = (1.0 + t * t).sqrt();
+
+        let xi = t.atan2(lambda_c);
+        let eta = (lambda_s / t_bar).atanh();
+
+        let (mut x, mut y) = (xi, eta);
+        for (j, alpha) in self.alpha.iter().enumerate() {
+            let m = 2.0 * (j + 1) as f64;
+            x += alpha * (m * xi).sin() * (m * eta).cosh();
+            y += alpha * (m * xi).cos() * (m * eta).sinh();
+        }
+        [self.radius * x - self.origin_arc, self.radius * y]
+    }
+
+    /// 原点からの北向きの距離 `x` と東向きの距離 `y`（m）を、緯度・経度（度）へ変換する。
+    pub(crate) fn inverse(&self, x: f64, y: f64) -> [f64; 2] {
+        let xi = (x + self.origin_arc) / self.radius;
+        let eta = y / self.radius;
+
+        let (mut xi_p, mut eta_p) = (xi, eta);
+        for (j, beta) in self.beta.iter().enumerate() {
+            let m = 2.0 * (j + 1) as f64;
+            xi_p -= beta * (m * xi).sin() * (m * eta).cosh();
+            eta_p -= beta * (m * xi).cos() * (m * eta).sinh();
+        }
+
+        let chi = (xi_p.sin() / eta_p.cosh()).asin();
+        let phi = chi
+            + self
+                .delta
+                .iter()
+                .enumerate()
+                .map(|(j, delta)| delta * (2.0 * (j + 1) as f64 * chi).sin())
+                .sum::<f64>();
+        let lambda = eta_p.sinh().atan2(xi_p.cos());
+
+        [
+            phi.to_degrees(),
+            self.central_meridian + lambda.to_degrees(),
+        ]
+    }
+}
@@ -0,0 +1,415 @@
+//! UTM 座標系と MGRS による座標の変換
+//!
+//! 経度 6 度ごとのゾーンで横メルカトル図法により投影した UTM 座標と
+//! [`Coordinate`](crate::geometry::coordinate::Coordinate) を相互に変換し、UTM 座標から MGRS（軍用グリッド参照系）の文字列を求めます。
+//! 極付近の UPS（ユニバーサル極心平射図法）は扱いません。
+
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate, ellipsoid::Ellipsoid, transverse_mercator::TransverseMercator,
+    },
+    spatial_id::single::SingleId,
+};
+
+/// UTM の中央子午線における縮尺係数
+const SCALE: f64 = 0.9996;
+
+/// UTM の東向きの座標に加える値（m）
+const FALSE_EASTING: f64 = 500_000.0;
+
+/// 南半球において北向きの座標に加える値（m）
+const FALSE_NORTHING: f64 = 10_000_000.0;
+
+/// UTM を適用する緯度の範囲（度）。これより極側では UPS を用いる。
+const LATITUDE_RANGE: (f64, f64) = (-80.0, 84.0);
+
+/// MGRS の緯度帯の文字（南緯 80 度から 8 度ごと。X 帯のみ 12 度）
+const BANDS: &[u8; 20] = b"CDEFGHJKLMNPQRSTUVWX";
+
+/// MGRS の 100km 四方の列の文字。ゾーンの番号を 3 で割った余りごとに 8 文字ずつ用いる。
+const COLUMNS: [&[u8; 8]; 3] = [b"ABCDEFGH", b"JKLMNPQR", b"STUVWXYZ"];
+
+/// MGRS の 100km 四方の行の文字。偶数のゾーンでは 5 文字ずらして用いる。
+const ROWS: &[u8; 20] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// UTM の半球
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    /// 北半球
+    North,
+    /// 南半球
+    South,
+}
+
+/// ユニバーサル横メルカトル（UTM）座標系における座標を表します。
+///
+/// 経度 6 度ごとの 60 のゾーンに分け、WGS-84 楕円体の緯度・経度を各ゾーンの中央子午線を
+/// 中心とする横メルカトル図法で投影したものです。東向きの座標は中央子午線を 500,000m とし、
+/// 南半球の北向きの座標は赤道を 10,000,000m とします。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::utm::{Hemisphere, Utm};
+/// // 東京駅
+/// let station = Coordinate::new(35.681236, 139.767125, 3.0).unwrap();
+/// let utm = Utm::from_coordinate(&station).unwrap();
+/// assert_eq!(utm.as_zone(), 54);
+/// assert_eq!(utm.as_hemisphere(), Hemisphere::North);
+/// assert!((utm.as_easting() - 388_435.7).abs() < 0.1);
+/// assert!((utm.as_northing() - 3_949_294.0).abs() < 0.1);
+///
+/// let back: Coordinate = utm.try_into().unwrap();
+/// assert!(back.geodesic_distance(&station) < 1e-6);
+///
+/// // ノルウェー南西部はゾーン 32 に含まれる
+/// let bergen = Coordinate::new(60.39, 5.32, 0.0).unwrap();
+/// assert_eq!(Utm::from_coordinate(&bergen).unwrap().as_zone(), 32);
+/// // スバールバル諸島ではゾーン 32・34・36 を用いない
+/// let longyearbyen = Coordinate::new(78.22, 15.65, 0.0).unwrap();
+/// assert_eq!(Utm::from_coordinate(&longyearbyen).unwrap().as_zone(), 33);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Utm {
+    zone: u8,
+    hemisphere: Hemisphere,
+    easting: f64,
+    northing: f64,
+    altitude: f64,
+}
+
+impl Utm {
+    /// ゾーンの番号（1〜60）・半球・東向きの座標（m）・北向きの座標（m）・高度（m）から
+    /// [`Utm`] を生成します。
+    ///
+    /// # 戻り値
+    /// * ゾーンの番号が `1..=60` の範囲外の場合は [`Error::ZoneOutOfRange`] を返します
+    pub fn new(
+        zone: u8,
+        hemisphere: Hemisphere,
+        easting: f64,
+        northing: f64,
+        altitude: f64,
+    ) -> Result<Utm, Error> {
+        if !(1..=60).contains(&zone) {
+            return Err(Error::ZoneOutOfRange { zone });
+        }
+        Ok(Utm {
+            zone,
+            hemisphere,
+            easting,
+            northing,
+            altitude,
+        })
+    }
+
+    /// [`Coordinate`] を、その座標が属するゾーンの [`Utm`] へ変換します。
+    ///
+    /// ノルウェー南西部とスバールバル諸島の例外を考慮してゾーンを定めます。
+    ///
+    /// # 戻り値
+    /// * 緯度が UTM の範囲（南緯 80 度〜北緯 84 度）外の場合は [`Error::UtmLatitudeOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::error::Error;
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::utm::Utm;
+    /// let arctic = Coordinate::new(84.5, 10.0, 0.0).unwrap();
+    /// assert_eq!(
+    ///     Utm::from_coordinate(&arctic),
+    ///     Err(Error::UtmLatitudeOutOfRange { latitude: 84.5 })
+    /// );
+    /// ```
+    pub fn from_coordinate(coordinate: &Coordinate) -> Result<Utm, Error> {
+        let (latitude, longitude) = (coordinate.as_latitude(), coordinate.as_longitude());
+        if !(LATITUDE_RANGE.0..=LATITUDE_RANGE.1).contains(&latitude) {
+            return Err(Error::UtmLatitudeOutOfRange { latitude });
+        }
+        Utm::from_coordinate_in_zone(coordinate, zone_of(latitude, longitude))
+    }
+
+    /// [`Coordinate`] を、指定されたゾーンの [`Utm`] へ変換します。
+    ///
+    /// 隣接するゾーンにまたがるデータを 1 つのゾーンで扱う場合に使用します。
+    pub fn from_coordinate_in_zone(coordinate: &Coordinate, zone: u8) -> Result<Utm, Error> {
+        let latitude = coordinate.as_latitude();
+        let [x, y] = projection(zone)?.forward(latitude, coordinate.as_longitude());
+        let (hemisphere, northing) = if latitude >= 0.0 {
+            (Hemisphere::North, x)
+        } else {
+            (Hemisphere::South, x + FALSE_NORTHING)
+        };
+        Utm::new(
+            zone,
+            hemisphere,
+            y + FALSE_EASTING,
+            northing,
+            coordinate.as_altitude(),
+        )
+    }
+
+    /// ゾーンの番号を返します。
+    pub fn as_zone(&self) -> u8 {
+        self.zone
+    }
+
+    /// 半球を返します。
+    pub fn as_hemisphere(&self) -> Hemisphere {
+        self.hemisphere
+    }
+
+    /// 東向きの座標（m）を返します。
+    pub fn as_easting(&self) -> f64 {
+        self.easting
+    }
+
+    /// 北向きの座標（m）を返します。
+    pub fn as_northing(&self) -> f64 {
+        self.northing
+    }
+
+    /// 高度（m）を返します。
+    pub fn as_altitude(&self) -> f64 {
+        self.altitude
+    }
+
+    /// [`Coordinate`] へ変換します。
+    ///
+    /// 変換後の座標が空間 ID 上で扱える範囲外の場合は、対応する `Error` を返します。
+    pub fn to_coordinate(self) -> Result<Coordinate, Error> {
+        let x = match self.hemisphere {
+            Hemisphere::North => self.northing,
+            Hemisphere::South => self.northing - FALSE_NORTHING,
+        };
+        let [latitude, longitude] = projection(self.zone)?.inverse(x, self.easting - FALSE_EASTING);
+        Coordinate::new(latitude, normalize_longitude(longitude), self.altitude)
+    }
+
+    /// 指定されたズームレベルの [`SingleId`] へ変換します。
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::utm::Utm;
+    /// let station = Coordinate::new(35.681236, 139.767125, 3.0).unwrap();
+    /// let utm = Utm::from_coordinate(&station).unwrap();
+    /// assert_eq!(utm.to_single_id(25).unwrap(), station.to_single_id(25));
+    /// ```
+    pub fn to_single_id(self, z: u8) -> Result<SingleId, Error> {
+        Ok(self.to_coordinate()?.to_single_id(z))
+    }
+
+    /// MGRS の文字列へ変換します。
+    ///
+    /// `precision` は東向き・北向きそれぞれの座標の桁数（0〜5）で、5 で 1m、0 で 100km の
+    /// 精度となります。5 を超える値は 5 として扱い、桁に満たない端数は切り捨てます。
+    ///
+    /// # 戻り値
+    /// * 緯度が UTM の範囲（南緯 80 度〜北緯 84 度）外の場合は [`Error::UtmLatitudeOutOfRange`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::utm::Utm;
+    /// let station = Coordinate::new(35.681236, 139.767125, 0.0).unwrap();
+    /// let utm = Utm::from_coordinate(&station).unwrap();
+    ///
+    /// assert_eq!(utm.to_mgrs(5).unwrap(), "54SUE8843549293");
+    /// assert_eq!(utm.to_mgrs(2).unwrap(), "54SUE8849");
+    /// assert_eq!(utm.to_mgrs(0).unwrap(), "54SUE");
+    ///
+    /// // エッフェル塔
+    /// let tower = Coordinate::new(48.8582, 2.2945, 0.0).unwrap();
+    /// let utm = Utm::from_coordinate(&tower).unwrap();
+    /// assert_eq!(utm.to_mgrs(5).unwrap(), "31UDQ4825111932");
+    /// ```
+    pub fn to_mgrs(self, precision: u8) -> Result<String, Error> {
+        let precision = precision.min(5) as i32;
+        let coordinate = self.to_coordinate()?;
+        let latitude = coordinate.as_latitude();
+        if !(LATITUDE_RANGE.0..=LATITUDE_RANGE.1).contains(&latitude) {
+            return Err(Error::UtmLatitudeOutOfRange { latitude });
+        }
+
+        let band = BANDS[(((latitude - LATITUDE_RANGE.0) / 8.0).floor() as usize).min(19)];
+        let column_index = (self.easting / 100_000.0).floor() as i64 - 1;
+        let row_index = (self.northing / 100_000.0).floor() as i64 + row_offset(self.zone);
+        let column = COLUMNS[(self.zone as usize - 1) % 3]
+            .get(usize::try_from(column_index).unwrap_or(usize::MAX))
+            .ok_or(Error::LongitudeOutOfRange {
+                longitude: coordinate.as_longitude(),
+            })?;
+        let row = ROWS[row_index.rem_euclid(20) as usize];
+
+        let mut mgrs = format!(
+            "{:02}{}{}{}",
+            self.zone, band as char, *column as char, row as char
+        );
+        if precision > 0 {
+            let unit = 10_f64.powi(5 - precision);
+            let easting = ((self.easting % 100_000.0) / unit).floor() as u32;
+            let northing = ((self.northing % 100_000.0) / unit).floor() as u32;
+            let width = precision as usize;
+            mgrs.push_str(&format!("{easting:0width$}{northing:0width$}"));
+        }
+        Ok(mgrs)
+    }
+
+    /// MGRS の文字列から [`Utm`] を生成します。
+    ///
+    /// 返される座標は、文字列が表す格子の南西端です。空白は無視し、英字の大文字と小文字を
+    /// 区別しません。高度は 0 とします。
+    ///
+    /// # 戻り値
+    /// * 文字列を解釈できない場合は [`Error::MgrsParse`] を返します
+    ///
+    /// ```
+    /// # use kasane_logic::geometry::coordinate::Coordinate;
+    /// # use kasane_logic::geometry::utm::{Hemisphere, Utm};
+    /// let utm = Utm::from_mgrs("54S UE 88435 49293").unwrap();
+    /// assert_eq!(utm.as_zone(), 54);
+    /// assert_eq!(utm.as_hemisphere(), Hemisphere::North);
+    /// assert_eq!(utm.as_easting(), 388_435.0);
+    /// assert_eq!(utm.as_northing(), 3_949_293.0);
+    ///
+    /// // 南半球（シドニー）
+    /// let sydney = Utm::from_mgrs("56HLH3487352266").unwrap();
+    /// let coordinate: Coordinate = sydney.try_into().unwrap();
+    /// assert!((coordinate.as_latitude() + 33.857).abs() < 1e-3);
+    /// assert!((coordinate.as_longitude() - 151.215).abs() < 1e-3);
+    ///
+    /// assert!(Utm::from_mgrs("54SUE88449").is_err());
+    /// assert!(Utm::from_mgrs("54IUE8849").is_err());
+    /// ```
+    pub fn from_mgrs(mgrs: &str) -> Result<Utm, Error> {
+        let error = || Error::MgrsParse {
+            mgrs: mgrs.to_string(),
+        };
+        let text: Vec<u8> = mgrs
+            .bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .map(|b| b.to_ascii_uppercase())
+            .collect();
+
+        let zone_len = text.iter().take_while(|b| b.is_ascii_digit()).count();
+        if !(1..=2).contains(&zone_len) || text.len() < zone_len + 3 {
+            return Err(error());
+        }
+        let zone: u8 = std::str::from_utf8(&text[..zone_len])
+            .ok()
+            .and_then(|z| z.parse().ok())
+            .ok_or_else(error)?;
+        let [band, column, row] = [0, 1, 2].map(|i| text[zone_len + i]);
+        let digits = &text[zone_len + 3..];
+        if !(1..=60).contains(&zone)
+            || !digits.len().is_multiple_of(2)
+            || digits.len() > 10
+            || !digits.iter().all(u8::is_ascii_digit)
+        {
+            return Err(error());
+        }
+
+        let band_index = BANDS.iter().position(|&b| b == band).ok_or_else(error)?;
+        let column_index = COLUMNS[(zone as usize - 1) % 3]
+            .iter()
+            .position(|&c| c == column)
+            .ok_or_else(error)?;
+        let row_index = ROWS.iter().position(|&r| r == row).ok_or_else(error)? as i64;
+
+        let precision = digits.len() / 2;
+        let unit = 10_f64.powi(5 - precision as i32);
+        let parse = |part: &[u8]| {
+            std::str::from_utf8(part)
+                .ok()
+                .and_then(|s| {
+                    if s.is_empty() {
+                        Some(0)
+                    } else {
+                        s.parse::<u32>().ok()
+                    }
+                })
+                .map(|v| v as f64 * unit)
+                .ok_or_else(error)
+        };
+        let easting = (column_index + 1) as f64 * 100_000.0 + parse(&digits[..precision])?;
+        let offset = (row_index - row_offset(zone)).rem_euclid(20);
+        let mut northing = offset as f64 * 100_000.0 + parse(&digits[precision..])?;
+
+        // 北向きの座標は 2,000km ごとに同じ文字が現れるため、緯度帯の南端から定める
+        let south = LATITUDE_RANGE.0 + band_index as f64 * 8.0;
+        let hemisphere = if band >= b'N' {
+            Hemisphere::North
+        } else {
+            Hemisphere::South
+        };
+        let [x, _] = projection(zone)?.forward(south, central_meridian(zone));
+        let band_northing = match hemisphere {
+            Hemisphere::North => x,
+            Hemisphere::South => x + FALSE_NORTHING,
+        } - 200_000.0;
+        while northing < band_northing {
+            northing += 2_000_000.0;
+        }
+
+        Utm::new(zone, hemisphere, easting, northing, 0.0)
+    }
+}
+
+impl TryFrom<Utm> for Coordinate {
+    type Error = Error;
+
+    fn try_from(value: Utm) -> Result<Self, Self::Error> {
+        value.to_coordinate()
+    }
+}
+
+/// 緯度・経度（度）が属する UTM のゾーンの番号を返す。
+fn zone_of(latitude: f64, longitude: f64) -> u8 {
+    // ノルウェー南西部
+    if (56.0..64.0).contains(&latitude) && (3.0..12.0).contains(&longitude) {
+        return 32;
+    }
+    // スバールバル諸島
+    if (72.0..=84.0).contains(&latitude) && (0.0..42.0).contains(&longitude) {
+        return match longitude {
+            l if l < 9.0 => 31,
+            l if l < 21.0 => 33,
+            l if l < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((longitude + 180.0) / 6.0).floor() as u8 + 1).min(60)
+}
+
+/// MGRS の行の文字のずれ（偶数のゾーンで 5）を返す。
+fn row_offset(zone: u8) -> i64 {
+    if zone.is_multiple_of(2) { 5 } else { 0 }
+}
+
+fn normalize_longitude(longitude: f64) -> f64 {
+    if longitude > 180.0 {
+        longitude - 360.0
+    } else if longitude < -180.0 {
+        longitude + 360.0
+    } else {
+        longitude
+    }
+}
+
+/// ゾーンの中央子午線の経度（度）を返す。
+fn central_meridian(zone: u8) -> f64 {
+    zone as f64 * 6.0 - 183.0
+}
+
+/// ゾーンに対応する横メルカトル図法を返す。
+///
+/// 図法の原点は赤道上とし、東向きの座標の加算値は含めない。
+fn projection(zone: u8) -> Result<TransverseMercator, Error> {
+    if !(1..=60).contains(&zone) {
+        return Err(Error::ZoneOutOfRange { zone });
+    }
+    Ok(TransverseMercator::new(
+        &Ellipsoid::WGS84,
+        SCALE,
+        0.0,
+        central_meridian(zone),
+    ))
+}
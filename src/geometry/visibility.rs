@@ -0,0 +1,288 @@
+//! 空間 ID の集合を障害物とした見通しの判定
+//!
+//! 障害物は [`Contains`](crate::spatial_id::set::Contains) を実装した空間 ID の集合として
+//! 与えます。光線は粗いズームレベルから順に走査し、障害物と共有部分を持たない空間 ID は
+//! 細分化せずにまとめて通過します。地形も障害物として与えない限り、地中の空間 ID は遮られません。
+
+use crate::{
+    error::Error,
+    geometry::{
+        coordinate::Coordinate,
+        ecef::Ecef,
+        helpers::{cell_bound, ecef_array, geodetic_to_matrix, is_folded, norm, sub},
+        shapes::{
+            Relation, Shape,
+            line::{
+                LineMode, TRACE_TOLERANCE, flatten_path, geodetic_path, index_to_single_id,
+                line_dda,
+            },
+            root_cells,
+            sphere::Sphere,
+        },
+    },
+    spatial_id::{SpatialId, constants::MAX_ZOOM_LEVEL, helpers, set::Contains, single::SingleId},
+};
+
+/// 光線を走査した結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum RayHit {
+    /// 終点まで遮られない。
+    Clear,
+    /// 空間 ID に遮られる。
+    Blocked {
+        /// 光線を遮る空間 ID のうち、光線が最初に入るもの
+        id: SingleId,
+        /// 始点から `id` に入る点までの距離（m）
+        distance: f64,
+    },
+}
+
+/// 始点から終点へ向かう ECEF 上の直線が、障害物の集合に遮られるかを判定する。
+///
+/// 遮られる場合は、光線が最初に入る障害物の空間 ID と、その空間 ID に入る点までの距離を返す。
+/// 返される空間 ID は、光線上で障害物に完全に含まれる最も粗い空間 ID であり、
+/// 始点が障害物の内部にある場合の距離は 0 となる。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::visibility::{raycast, RayHit};
+/// # use kasane_logic::spatial_id::set::SingleIdSet;
+/// let origin = Coordinate::new(35.0, 139.0, 50.0).unwrap();
+/// let target = Coordinate::new(35.0, 139.01, 50.0).unwrap();
+///
+/// // 中間地点の高さ 128m の建物と、終点付近の細かい障害物
+/// let building = Coordinate::new(35.0, 139.005, 50.0).unwrap().to_single_id(18);
+/// let mast = Coordinate::new(35.0, 139.009, 50.0).unwrap().to_single_id(22);
+/// let occupied: SingleIdSet = [building.clone(), mast].into_iter().collect();
+///
+/// match raycast(origin, target, &occupied) {
+///     RayHit::Blocked { id, distance } => {
+///         assert_eq!(id, building);
+///         assert!(300.0 < distance && distance < 460.0);
+///     }
+///     RayHit::Clear => panic!("the building blocks the ray"),
+/// }
+///
+/// // 建物より高い位置を通る光線は遮られない
+/// let high = Coordinate::new(35.0, 139.0, 300.0).unwrap();
+/// let high_target = Coordinate::new(35.0, 139.01, 300.0).unwrap();
+/// assert_eq!(raycast(high, high_target, &occupied), RayHit::Clear);
+/// ```
+pub fn raycast(origin: Coordinate, target: Coordinate, occupied: &impl Contains) -> RayHit {
+    let z = occupied.max_z();
+    let path = geodetic_path(origin, target, LineMode::Chord);
+    let points = flatten_path(
+        &|t| {
+            let [lat, lon, alt] = path(t);
+            geodetic_to_matrix(lat, lon, alt, z)
+        },
+        TRACE_TOLERANCE,
+    );
+
+    let start = ecef_array(origin.into());
+    for pair in points.windows(2) {
+        if let Some((id, [f, x, y])) = walk(occupied, 0, z, pair[0], pair[1]) {
+            let entry = Ecef::from_geodetic_unchecked([
+                helpers::latitude(y, z),
+                helpers::longitude(x, z),
+                helpers::altitude(f, z),
+            ]);
+            return RayHit::Blocked {
+                id,
+                distance: norm(sub(ecef_array(entry), start)),
+            };
+        }
+    }
+    RayHit::Clear
+}
+
+/// 視点から距離 `range`（m）以内にあり、障害物に遮られずに見通せるズームレベル `z` の空間 ID を
+/// 昇順に返す。
+///
+/// 空間 ID は中心点への光線が遮られない場合に見えるものとし、障害物と共有部分を持つ空間 ID は
+/// 含まない。`layer` を指定した場合は、F インデックスが `layer` の層に限定する。
+///
+/// 粗いズームレベルから順に、視点と空間 ID を結ぶ錐体に障害物が含まれるかを判定し、
+/// 障害物を含まない空間 ID は子孫をまとめて見えるものとする。障害物を含む空間 ID のみを
+/// `z` まで細分化し、[`raycast`] で個別に判定する。
+///
+/// ```
+/// # use kasane_logic::geometry::coordinate::Coordinate;
+/// # use kasane_logic::geometry::visibility::viewshed;
+/// # use kasane_logic::spatial_id::set::SingleIdSet;
+/// // 地上 60m の監視局と、その東側にある高さ 128m の建物
+/// let station = Coordinate::new(35.0, 139.0, 60.0).unwrap();
+/// let building = Coordinate::new(35.0, 139.001, 50.0).unwrap().to_single_id(18);
+/// let obstacles: SingleIdSet = [building.clone()].into_iter().collect();
+///
+/// // 高度 32m 以上 64m 未満の層のうち、半径 300m 以内で見通せる空間 ID
+/// let visible = viewshed(20, station, 300.0, &obstacles, Some(1)).unwrap();
+/// assert!(visible.iter().all(|id| id.as_f() == 1));
+///
+/// let west = Coordinate::new(35.0, 138.998, 50.0).unwrap().to_single_id(20);
+/// let east = Coordinate::new(35.0, 139.0025, 50.0).unwrap().to_single_id(20);
+/// let inside = Coordinate::new(35.0, 139.001, 50.0).unwrap().to_single_id(20);
+/// assert!(visible.binary_search(&west).is_ok());
+/// assert!(visible.binary_search(&east).is_err());
+/// assert!(visible.binary_search(&inside).is_err());
+/// ```
+pub fn viewshed(
+    z: u8,
+    observer: Coordinate,
+    range: f64,
+    obstacles: &impl Contains,
+    layer: Option<i32>,
+) -> Result<Vec<SingleId>, Error> {
+    if z > MAX_ZOOM_LEVEL as u8 {
+        return Err(Error::ZOutOfRange { z });
+    }
+    if range.is_nan() || range < 0.0 {
+        return Err(Error::RadiusOutOfRange { radius: range });
+    }
+
+    let reach = Sphere::new(observer, range);
+    let apex = ecef_array(observer.into());
+    let mut visible = Vec::new();
+    // 空間 ID と、祖先の錐体に障害物が含まれないことが確定しているか
+    let mut stack: Vec<(SingleId, bool)> = root_cells().into_iter().map(|id| (id, false)).collect();
+
+    while let Some((id, clear)) = stack.pop() {
+        if is_folded(&id) {
+            continue;
+        }
+        if let Some(layer) = layer
+            && layer >> (z - id.as_z()) != id.as_f()
+        {
+            continue;
+        }
+        let within = reach.relation(&id);
+        if within == Relation::Outside {
+            continue;
+        }
+
+        // 錐体に障害物が含まれなければ、空間 ID 内のすべての点を見通せる
+        let clear = clear || !cone_blocked(obstacles, apex, &id);
+        if id.as_z() == z {
+            let center = id.center();
+            let accept = if clear {
+                within == Relation::Inside || reach.contains(&center)
+            } else {
+                !obstacles.intersects(&id)
+                    && reach.contains(&center)
+                    && raycast(observer, center, obstacles) == RayHit::Clear
+            };
+            if accept {
+                visible.push(id);
+            }
+        } else if clear && within == Relation::Inside && layer.is_none() {
+            visible.extend(id.children(z - id.as_z()).into_iter().flatten());
+        } else {
+            stack.extend(
+                id.children(1)
+                    .into_iter()
+                    .flatten()
+                    .map(|child| (child, clear)),
+            );
+        }
+    }
+
+    visible.sort();
+    Ok(visible)
+}
+
+/// 視点 `apex` と空間 ID を内包する球を結ぶ錐体が、障害物と共有部分を持つ可能性があるかを返す。
+fn cone_blocked(obstacles: &impl Contains, apex: [f64; 3], id: &SingleId) -> bool {
+    let (center, radius) = cell_bound(id);
+    let axis = sub(center, apex);
+    // 錐体は、視点から球の中心へ向かう線分上の点 `apex + λ·axis` を中心とする半径 `λ·radius` の球の和
+    let touches = |cell: &SingleId| {
+        let (q, r) = cell_bound(cell);
+        let u = sub(q, apex);
+        let distance = |l: f64| norm(sub(u, axis.map(|v| v * l))) - l * radius;
+        // `distance` は λ について凸であるため、三分探索で最小値を求める
+        let (mut lo, mut hi) = (0.0_f64, 1.0_f64);
+        for _ in 0..48 {
+            let m1 = lo + (hi - lo) / 3.0;
+            let m2 = hi - (hi - lo) / 3.0;
+            if distance(m1) <= distance(m2) {
+                hi = m2;
+            } else {
+                lo = m1;
+            }
+        }
+        distance((lo + hi) / 2.0) <= r
+    };
+
+    let max_z = obstacles.max_z();
+    let mut stack = root_cells().to_vec();
+    while let Some(cell) = stack.pop() {
+        if !obstacles.intersects(&cell) || !touches(&cell) {
+            continue;
+        }
+        if cell.as_z() >= max_z || obstacles.contains(&cell) {
+            return true;
+        }
+        stack.extend(cell.children(1).into_iter().flatten());
+    }
+    false
+}
+
+/// ズームレベル `z` のインデックス空間上の線分 `a`→`b` に沿って、ズームレベル `level` の
+/// 空間 ID を走査し、最初に遮る空間 ID とその空間 ID に入る点を返す。
+///
+/// 障害物と共有部分を持つが完全には含まれない空間 ID は、通過する区間のみを 1 段階細かい
+/// ズームレベルで走査し直す。
+fn walk(
+    occupied: &impl Contains,
+    level: u8,
+    z: u8,
+    a: [f64; 3],
+    b: [f64; 3],
+) -> Option<(SingleId, [f64; 3])> {
+    let size = 2_f64.powi(z as i32 - level as i32);
+    for cell in line_dda(a.map(|v| v / size), b.map(|v| v / size)) {
+        let Some(id) = index_to_single_id(level, cell) else {
+            continue;
+        };
+        if !occupied.intersects(&id) {
+            continue;
+        }
+        let Some((t0, t1)) = clip(a, b, cell.map(|v| v as f64 * size), size) else {
+            continue;
+        };
+
+        let entry = lerp(a, b, t0);
+        if level >= z || occupied.contains(&id) {
+            return Some((id, entry));
+        }
+        if let Some(hit) = walk(occupied, level + 1, z, entry, lerp(a, b, t1)) {
+            return Some(hit);
+        }
+    }
+    None
+}
+
+/// 線分 `a`→`b` のうち、`lower` を最小の頂点とする 1 辺 `size` の立方体を通過する区間を、
+/// 媒介変数の範囲 `(t0, t1)` で返す。
+fn clip(a: [f64; 3], b: [f64; 3], lower: [f64; 3], size: f64) -> Option<(f64, f64)> {
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+    for i in 0..3 {
+        let (lo, hi) = (lower[i], lower[i] + size);
+        let d = b[i] - a[i];
+        if d == 0.0 {
+            if a[i] < lo || a[i] > hi {
+                return None;
+            }
+            continue;
+        }
+        let (s0, s1) = ((lo - a[i]) / d, (hi - a[i]) / d);
+        t0 = t0.max(s0.min(s1));
+        t1 = t1.min(s0.max(s1));
+    }
+    // 境界上を通過する場合の丸め誤差を許容する
+    (t0 <= t1 + 1e-9).then_some((t0, t1.max(t0)))
+}
+
+fn lerp(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
@@ -4,7 +4,11 @@ use std::fmt;
 
 use crate::{
     error::Error,
-    geometry::{coordinate::Coordinate, ecef::Ecef},
+    geometry::{
+        coordinate::Coordinate,
+        ecef::Ecef,
+        shapes::{Relation, Shape},
+    },
     spatial_id::{
         SpatialId,
         constants::{F_MAX, F_MIN, MAX_ZOOM_LEVEL, XY_MAX},
@@ -623,3 +627,57 @@ impl From<SingleId> for RangeId {
         }
     }
 }
+
+/// [`RangeId`] を図形として扱い、[`half_space`](crate::geometry::shapes::half_space::half_space)
+/// の切り取り範囲や [`csg`](crate::geometry::shapes::csg) の合成に使用できるようにします。
+///
+/// ```
+/// # use kasane_logic::spatial_id::range::RangeId;
+/// # use kasane_logic::geometry::shapes::{Mode, Shape};
+/// let range = RangeId::new(4, [0, 1], [2, 3], [5, 5]).unwrap();
+/// let cells = range.cover(5, Mode::Strict).unwrap();
+///
+/// assert_eq!(cells.len(), range.to_single().count() * 8);
+/// ```
+impl Shape for RangeId {
+    fn relation(&self, id: &SingleId) -> Relation {
+        // 細かい方のズームレベルでのインデックス範囲に揃えて比較する
+        let z = self.z.max(id.as_z());
+        let scale = |value: i64, from: u8| value * (1_i64 << (z - from));
+        let extent = |[min, max]: [i64; 2], from: u8| [scale(min, from), scale(max + 1, from) - 1];
+
+        let range = [
+            extent([self.f[0] as i64, self.f[1] as i64], self.z),
+            extent([self.x[0] as i64, self.x[1] as i64], self.z),
+            extent([self.y[0] as i64, self.y[1] as i64], self.z),
+        ];
+        let cell = [
+            extent([id.as_f() as i64; 2], id.as_z()),
+            extent([id.as_x() as i64; 2], id.as_z()),
+            extent([id.as_y() as i64; 2], id.as_z()),
+        ];
+
+        if range
+            .iter()
+            .zip(&cell)
+            .any(|(r, c)| c[1] < r[0] || r[1] < c[0])
+        {
+            Relation::Outside
+        } else if range
+            .iter()
+            .zip(&cell)
+            .all(|(r, c)| r[0] <= c[0] && c[1] <= r[1])
+        {
+            Relation::Inside
+        } else {
+            Relation::Intersects
+        }
+    }
+
+    fn contains(&self, point: &Coordinate) -> bool {
+        let id = point.to_single_id(self.z);
+        (self.f[0]..=self.f[1]).contains(&id.as_f())
+            && (self.x[0]..=self.x[1]).contains(&id.as_x())
+            && (self.y[0]..=self.y[1]).contains(&id.as_y())
+    }
+}
@@ -0,0 +1,138 @@
+//! 空間 ID の集合
+//!
+//! 異なるズームレベルの空間 ID を混在させて保持し、任意の空間 ID が集合に
+//! 含まれるか、集合と共有部分を持つかを判定する機能を提供します。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::spatial_id::{constants::MAX_ZOOM_LEVEL, single::SingleId};
+
+/// 空間 ID の集合が備えるべき包含判定の性質を定義するトレイト。
+///
+/// 粗いズームレベルの空間 ID で判定を打ち切れるよう、完全な包含と
+/// 共有部分の有無を区別して返す。
+pub trait Contains {
+    /// 空間 ID の全体が集合に含まれるかを返す。
+    ///
+    /// 確実な場合にのみ `true` を返せばよいが、[`Contains::max_z`] 以上のズームレベルの
+    /// 空間 ID に対しては [`Contains::intersects`] と一致しなければならない。
+    fn contains(&self, id: &SingleId) -> bool;
+
+    /// 空間 ID が集合と共有部分を持つかを返す。
+    fn intersects(&self, id: &SingleId) -> bool;
+
+    /// 集合に含まれる空間 ID のうち、最も細かいズームレベルを返す。
+    fn max_z(&self) -> u8;
+}
+
+/// ズームレベルの異なる [`SingleId`] を混在させて保持する集合
+///
+/// 各要素の祖先を索引として保持するため、[`Contains::intersects`] は
+/// 要素の個数によらず空間 ID のズームレベルに比例する回数の探索で判定できる。
+///
+/// ```
+/// # use kasane_logic::spatial_id::single::SingleId;
+/// # use kasane_logic::spatial_id::set::{Contains, SingleIdSet};
+/// let mut set = SingleIdSet::new();
+/// set.insert(SingleId::new(10, 1, 908, 403).unwrap());
+///
+/// let child = SingleId::new(12, 4, 3632, 1612).unwrap();
+/// let parent = SingleId::new(8, 0, 227, 100).unwrap();
+/// assert!(set.contains(&child));
+/// assert!(!set.contains(&parent));
+/// assert!(set.intersects(&parent));
+/// assert_eq!(set.max_z(), 10);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SingleIdSet {
+    members: BTreeSet<SingleId>,
+    /// 要素の真の祖先と、その祖先を持つ要素の個数
+    ancestors: BTreeMap<SingleId, usize>,
+    /// ズームレベルごとの要素の個数
+    zooms: [usize; MAX_ZOOM_LEVEL + 1],
+}
+
+impl SingleIdSet {
+    /// 空の [`SingleIdSet`] を生成します。
+    pub fn new() -> SingleIdSet {
+        SingleIdSet::default()
+    }
+
+    /// 空間 ID を追加します。既に含まれていた場合は `false` を返します。
+    pub fn insert(&mut self, id: SingleId) -> bool {
+        if self.members.contains(&id) {
+            return false;
+        }
+        for difference in 1..=id.as_z() {
+            if let Some(ancestor) = id.parent(difference) {
+                *self.ancestors.entry(ancestor).or_default() += 1;
+            }
+        }
+        self.zooms[id.as_z() as usize] += 1;
+        self.members.insert(id)
+    }
+
+    /// 空間 ID を削除します。含まれていなかった場合は `false` を返します。
+    pub fn remove(&mut self, id: &SingleId) -> bool {
+        if !self.members.remove(id) {
+            return false;
+        }
+        self.zooms[id.as_z() as usize] -= 1;
+        for difference in 1..=id.as_z() {
+            if let Some(ancestor) = id.parent(difference)
+                && let Some(count) = self.ancestors.get_mut(&ancestor)
+            {
+                *count -= 1;
+                if *count == 0 {
+                    self.ancestors.remove(&ancestor);
+                }
+            }
+        }
+        true
+    }
+
+    /// 要素の個数を返します。
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// 要素を持たない場合に `true` を返します。
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// 要素を昇順に列挙します。
+    pub fn iter(&self) -> impl Iterator<Item = &SingleId> {
+        self.members.iter()
+    }
+}
+
+impl FromIterator<SingleId> for SingleIdSet {
+    fn from_iter<T: IntoIterator<Item = SingleId>>(iter: T) -> Self {
+        let mut set = SingleIdSet::new();
+        for id in iter {
+            set.insert(id);
+        }
+        set
+    }
+}
+
+impl Contains for SingleIdSet {
+    /// 空間 ID 自身またはその祖先が要素である場合に `true` を返す。
+    ///
+    /// 細かい要素の組み合わせで覆われている場合は `false` を返す。
+    fn contains(&self, id: &SingleId) -> bool {
+        (0..=id.as_z()).any(|difference| {
+            id.parent(difference)
+                .is_some_and(|ancestor| self.members.contains(&ancestor))
+        })
+    }
+
+    fn intersects(&self, id: &SingleId) -> bool {
+        self.ancestors.contains_key(id) || self.contains(id)
+    }
+
+    fn max_z(&self) -> u8 {
+        self.zooms.iter().rposition(|&count| count > 0).unwrap_or(0) as u8
+    }
+}
@@ -634,16 +634,10 @@ impl SpatialId for SingleId {
         // 結果配列（Default を利用）
         let mut out = [Coordinate::default(); 8];
 
+        // Y の両端の緯度は ±85.05112878° となり、`Coordinate::new` の検証範囲をわずかに超えるため
+        // 検証を行わずに生成する
         for (i, (f_i, y_i, x_i)) in iproduct!(0..2, 0..2, 0..2).enumerate() {
-            out[i]
-                .set_longitude(lon2[x_i])
-                .expect("longitude must be within valid range");
-            out[i]
-                .set_latitude(lat2[y_i])
-                .expect("latitude must be within valid range");
-            out[i]
-                .set_altitude(alt2[f_i])
-                .expect("altitude must be within valid range");
+            out[i] = unsafe { Coordinate::uncheck_new(lat2[y_i], lon2[x_i], alt2[f_i]) };
         }
 
         out